@@ -7,6 +7,7 @@ use std::{
     fmt::Display
 };
 
+use crate::auth::{Scope, ScopeRequirement, TokenType};
 use crate::util::Result;
 
 #[derive(Clone)]
@@ -24,7 +25,9 @@ pub struct TwitchApiCall<'a, T = ()> {
     method: Method,
     params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     body: Option<T>,
-    scope: Option<String>,
+    form: bool,
+    scopes: ScopeRequirement,
+    token_type: Option<TokenType>,
 }
 
 impl<'a> TwitchApiCall<'a> {
@@ -59,16 +62,37 @@ impl<'a, T> TwitchApiCall<'a, T> {
             TwitchApiCallType::Custom => self.url.parse().unwrap()
         };
 
-        Url::parse_with_params(uri.to_string().as_str(), self.params.to_vec()).unwrap()
+        match self.form {
+            true => Url::parse(uri.to_string().as_str()).unwrap(),
+            false => Url::parse_with_params(uri.to_string().as_str(), self.params.to_vec()).unwrap()
+        }
     }
 
-    pub fn scope(&self) -> Option<&str> {
-        self.scope.as_ref().map(String::as_str)
+    // the params as an application/x-www-form-urlencoded body, if the call sends them that way
+    pub fn form_body(&self) -> Option<String> {
+        match self.form {
+            true => Some(url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(self.params.iter())
+                .finish()),
+            false => None
+        }
+    }
+
+    pub fn scopes(&self) -> &ScopeRequirement {
+        &self.scopes
+    }
+
+    pub fn token_type(&self) -> Option<TokenType> {
+        self.token_type
     }
 
     pub fn method(&self) -> Method {
         self.method.clone()
     }
+
+    pub fn body(&self) -> Option<&T> {
+        self.body.as_ref()
+    }
 }
 
 #[derive(Clone)]
@@ -78,7 +102,9 @@ pub struct TwitchAPICallBuilder<'a, T = ()> {
     __method: Method,
     __params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
     __body: Option<T>,
-    __scope: Option<String>,
+    __form: bool,
+    __scopes: ScopeRequirement,
+    __token_type: Option<TokenType>,
 }
 
 impl<'a, T> TwitchAPICallBuilder<'a, T> {
@@ -89,7 +115,9 @@ impl<'a, T> TwitchAPICallBuilder<'a, T> {
             __method: Method::GET,
             __params: Vec::new(),
             __body: None,
-            __scope: None,
+            __form: false,
+            __scopes: ScopeRequirement::new(),
+            __token_type: None,
         }
     }
 
@@ -113,13 +141,29 @@ impl<'a, T> TwitchAPICallBuilder<'a, T> {
         self
     }
 
+    // keeps the params out of the URL, which ends up in error messages; used for secrets
+    pub fn with_form_params(mut self) -> Self {
+        self.__form = true;
+        self
+    }
+
     pub fn with_param(mut self, key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
         self.__params.push((key.into(), value.into()));
         self
     }
 
-    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
-        self.__scope = Some(scope.into());
+    pub fn with_scope(mut self, scope: Scope) -> Self {
+        self.__scopes.require(scope);
+        self
+    }
+
+    pub fn with_any_scope(mut self, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        self.__scopes.require_any(scopes);
+        self
+    }
+
+    pub fn with_token_type(mut self, token_type: TokenType) -> Self {
+        self.__token_type = Some(token_type);
         self
     }
 
@@ -136,7 +180,9 @@ impl<'a, T> TwitchAPICallBuilder<'a, T> {
             method: self.__method,
             params: self.__params,
             body: self.__body,
-            scope: self.__scope,
+            form: self.__form,
+            scopes: self.__scopes,
+            token_type: self.__token_type,
         })
     }
 }
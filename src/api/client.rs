@@ -1,34 +1,152 @@
-use crate::auth::{AuthProvider, AccessToken};
+use crate::auth::{AuthProvider, AuthProviderError, AccessToken, MultiUserAuthProvider, Scope, Secret, TokenType, UserInfo};
 use crate::api::{TwitchApiCall, TwitchApiCallType, TokenInfo, TokenInfoData};
+use crate::api::helix::{HelixCache, DEFAULT_CACHE_TTL};
 use crate::util::Result;
 use hyper::{Client, Body};
 use hyper_tls::HttpsConnector;
 use hyper::client::HttpConnector;
-use http::{Method, Request, Response};
-use crate::{User, UserResponse};
+use http::{Method, Request, Response, StatusCode};
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::Arc,
+    time::Duration,
+};
 
 #[repr(C)]
+#[derive(Clone)]
 pub struct ApiClient {
-    auth: Box<dyn AuthProvider + Sync + Send>,
+    app_auth: Option<Arc<dyn AuthProvider>>,
+    user_auth: Option<Arc<dyn AuthProvider>>,
+    extension_auth: Option<Arc<dyn AuthProvider>>,
+    users: Option<Arc<MultiUserAuthProvider>>,
+    user_context: Option<String>,
+    cache: Arc<HelixCache>,
 }
 
 impl ApiClient {
-    pub fn new(auth: Box<dyn AuthProvider + Sync + Send>) -> ApiClient {
+    pub fn new(auth: Arc<dyn AuthProvider>) -> ApiClient {
         ApiClient {
-            auth
+            app_auth: None,
+            user_auth: None,
+            extension_auth: None,
+            users: None,
+            user_context: None,
+            cache: Arc::new(HelixCache::new(DEFAULT_CACHE_TTL)),
+        }.with_auth_provider(auth)
+    }
+
+    // providers are routed by the kind of token they produce, replacing any previous one of the same kind
+    pub fn with_auth_provider(mut self, auth: Arc<dyn AuthProvider>) -> Self {
+        match auth.token_type() {
+            TokenType::App => self.app_auth = Some(auth),
+            TokenType::User => self.user_auth = Some(auth),
+            TokenType::Extension => self.extension_auth = Some(auth),
         }
+        self
     }
 
-    pub async fn call_api<T, B>(&mut self, call: TwitchApiCall<'_, B>) -> Result<T>
-        where T: serde::de::DeserializeOwned {
-        let token = match call.scope() {
-            Some(scope) => self.auth.access_token_with_scopes(vec![scope]),
-            None => self.auth.access_token()
-        }.await?;
-        if token.is_expired() {
-            // TODO
+    pub fn with_user_provider(mut self, users: Arc<MultiUserAuthProvider>) -> Self {
+        self.users = Some(users);
+        self
+    }
+
+    // a zero TTL turns caching of user and game lookups off
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache = Arc::new(HelixCache::new(ttl));
+        self
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.users.clear();
+        self.cache.games.clear();
+    }
+
+    pub(crate) fn cache(&self) -> &HelixCache {
+        &self.cache
+    }
+
+    pub fn users(&self) -> Option<&MultiUserAuthProvider> {
+        self.users.as_deref()
+    }
+
+    // clones are cheap and share all providers, so the user context lives on a copy of the client
+    pub fn as_user(&self, user_id: impl Into<String>) -> ApiClient {
+        let mut client = self.clone();
+        client.user_context = Some(user_id.into());
+        client
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_context.as_deref()
+    }
+
+    // the user the calls are made as: the user context if set, otherwise the owner of the user token
+    pub async fn token_user_id(&self) -> Result<String> {
+        if let Some(user_id) = self.user_context.as_ref() {
+            return Ok(user_id.clone());
+        }
+        let auth = match self.user_auth.as_ref() {
+            Some(auth) => auth,
+            None => return Err(Box::new(AuthProviderError::new("Can not determine the user without a user auth provider")))
+        };
+        let token = auth.access_token().await?;
+        if let Some(user_id) = token.user_id() {
+            return Ok(user_id.to_string());
+        }
+        // tokens that were never validated don't know their user yet; keep what we learn so this happens once
+        let token_info = Self::get_token_info_for_access_token(auth.client_id(), token.access_token()).await?;
+        auth.set_access_token(token.with_token_info(&token_info));
+        match token_info.user_id() {
+            Some(user_id) => Ok(user_id.to_string()),
+            None => Err(Box::new(AuthProviderError::new("The user auth provider returned a token without a user")))
         }
-        Self::call_api_with_credentials(call, self.auth.client_id(), token.access_token().to_string()).await
+    }
+
+    pub async fn call_api<T, B>(&self, call: TwitchApiCall<'_, B>) -> Result<T>
+        where T: serde::de::DeserializeOwned, B: serde::Serialize {
+        let (client_id, token) = self.credentials_for_call(&call).await?;
+        Self::call_api_with_credentials(call, client_id, token.access_token()).await
+    }
+
+    pub(crate) async fn credentials_for_call<B>(&self, call: &TwitchApiCall<'_, B>) -> Result<(String, AccessToken)> {
+        let user_context = match call.token_type() {
+            Some(TokenType::App) | Some(TokenType::Extension) => None,
+            _ => self.user_context.as_ref()
+        };
+        if let Some(user_id) = user_context {
+            let users = match self.users.as_ref() {
+                Some(users) => users,
+                None => return Err(Box::new(AuthProviderError::new(format!(
+                    "Can not make a call as user {} without a multi-user auth provider",
+                    user_id))))
+            };
+            let token = users.access_token_for_user(user_id.as_str(), call.scopes()).await?;
+            return Ok((users.client_id().to_string(), token));
+        }
+
+        let auth = match call.token_type() {
+            Some(TokenType::App) => self.app_auth.as_ref(),
+            Some(TokenType::User) => self.user_auth.as_ref(),
+            Some(TokenType::Extension) => self.extension_auth.as_ref(),
+            None => match self.user_auth.is_some() {
+                true => self.user_auth.as_ref(),
+                false => self.app_auth.as_ref()
+            }
+        };
+        let auth = match auth {
+            Some(auth) => auth,
+            None => return Err(Box::new(TokenTypeError::new(
+                call.full_url().to_string(),
+                call.token_type(),
+            )))
+        };
+        // providers that can refresh their token do so on their own once it expires
+        let token = match call.scopes().is_empty() {
+            true => auth.access_token(),
+            false => auth.access_token_with_scopes(call.scopes().clone())
+        }.await?;
+        Ok((auth.client_id(), token))
     }
 
     fn get_http_client() -> &'static Client<HttpsConnector<HttpConnector>> {
@@ -46,15 +164,43 @@ impl ApiClient {
             true => {
                 let body = res.into_body();
                 let chunk = hyper::body::to_bytes(body).await?;
+                // 204 No Content and friends; lets callers ask for () or an Option
+                let chunk = match chunk.is_empty() {
+                    true => hyper::body::Bytes::from_static(b"null"),
+                    false => chunk
+                };
                 let data: T = serde_json::from_slice(chunk.as_ref())?;
                 Ok(data)
             }
-            false => bail!(format!("request to {} failed with status {}", url_str, res.status()))
+            false => {
+                let status = res.status();
+                let chunk = hyper::body::to_bytes(res.into_body()).await?;
+                let message = match serde_json::from_slice::<ErrorResponse>(chunk.as_ref()) {
+                    Ok(error) => error.message,
+                    Err(_) => String::from_utf8_lossy(chunk.as_ref()).into_owned()
+                };
+                Err(Box::new(HttpStatusError::new(url_str, status, message)))
+            }
+        }
+    }
+
+    fn request_with_body<B>(req: http::request::Builder, call: &TwitchApiCall<'_, B>) -> Result<Request<Body>>
+        where B: serde::Serialize {
+        if let Some(form) = call.form_body() {
+            return Ok(req
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .body(Body::from(form))?);
+        }
+        match call.body() {
+            Some(body) => Ok(req
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(body)?))?),
+            None => Ok(req.body(Body::empty())?)
         }
     }
 
     pub async fn call_api_without_credentials<T, B>(call: TwitchApiCall<'_, B>) -> Result<T>
-        where T: serde::de::DeserializeOwned {
+        where T: serde::de::DeserializeOwned, B: serde::Serialize {
         let url = call.full_url();
         let url_str = url.into_string();
         let uri: hyper::Uri = url_str.parse()?;
@@ -63,13 +209,13 @@ impl ApiClient {
             .uri(uri)
             .method(call.method());
 
-        let res = Self::get_http_client().request(req.body(Body::empty())?).await?;
+        let res = Self::get_http_client().request(Self::request_with_body(req, &call)?).await?;
 
         Self::transform_response(url_str, res).await
     }
 
-    pub async fn call_api_with_credentials<T, B>(call: TwitchApiCall<'_, B>, client_id: impl ToString, access_token: impl ToString) -> Result<T>
-        where T: serde::de::DeserializeOwned {
+    pub async fn call_api_with_credentials<T, B>(call: TwitchApiCall<'_, B>, client_id: impl ToString, access_token: &Secret) -> Result<T>
+        where T: serde::de::DeserializeOwned, B: serde::Serialize {
         let url = call.full_url();
         let url_str = url.into_string();
         let uri: hyper::Uri = url_str.parse()?;
@@ -77,74 +223,310 @@ impl ApiClient {
         let req = Request::builder()
             .uri(uri)
             .header("Client-ID", client_id.to_string())
-            .header("Authorization", format!("Bearer {}", access_token.to_string()))
+            .header("Authorization", format!("Bearer {}", access_token.expose()))
             .method(call.method());
 
-        let res = Self::get_http_client().request(req.body(Body::empty())?).await?;
+        let res = Self::get_http_client().request(Self::request_with_body(req, &call)?).await?;
 
         Self::transform_response(url_str, res).await
     }
 
-    pub async fn get_app_access_token(client_id: impl ToString, client_secret: impl ToString) -> Result<AccessToken> {
+    pub async fn get_app_access_token(client_id: impl ToString, client_secret: &Secret) -> Result<AccessToken> {
         let call = TwitchApiCall::builder_empty()
             .with_call_type(TwitchApiCallType::Auth)
             .with_url("token")
             .with_method(Method::POST)
+            .with_form_params()
             .with_param("grant_type", "client_credentials")
             .with_param("client_id", client_id.to_string())
-            .with_param("client_secret", client_secret.to_string())
+            .with_param("client_secret", client_secret.expose())
             .build()?;
 
         let response = Self::call_api_without_credentials(call).await?;
         Ok(AccessToken::new(response))
     }
 
-    pub async fn refresh_access_token(client_id: impl ToString, client_secret: impl ToString, refresh_token: impl ToString) -> Result<AccessToken> {
-        let call = TwitchApiCall::builder_empty()
+    pub async fn refresh_access_token(client_id: impl ToString, client_secret: &Secret, refresh_token: &Secret) -> Result<AccessToken> {
+        let call = Self::refresh_access_token_call(client_id.to_string(), client_secret, refresh_token)?;
+        let response = Self::call_api_without_credentials(call).await?;
+        Ok(AccessToken::new(response))
+    }
+
+    fn refresh_access_token_call<'a>(client_id: String, client_secret: &'a Secret, refresh_token: &'a Secret) -> Result<TwitchApiCall<'a>> {
+        TwitchApiCall::builder_empty()
             .with_call_type(TwitchApiCallType::Auth)
             .with_url("token")
             .with_method(Method::POST)
+            .with_form_params()
             .with_param("grant_type", "refresh_token")
-            .with_param("client_id", client_id.to_string())
-            .with_param("client_secret", client_secret.to_string())
-            .with_param("refresh_token", refresh_token.to_string())
-            .build()?;
-
-        let response = Self::call_api_without_credentials(call).await?;
-        Ok(AccessToken::new(response))
+            .with_param("client_id", client_id)
+            .with_param("client_secret", client_secret.expose())
+            .with_param("refresh_token", refresh_token.expose())
+            .build()
     }
 
-    pub async fn get_token_info_for_access_token(client_id: impl ToString, access_token: impl ToString) -> Result<TokenInfo> {
+    pub async fn get_token_info_for_access_token(client_id: impl ToString, access_token: &Secret) -> Result<TokenInfo> {
         let call = TwitchApiCall::builder_empty()
             .with_call_type(TwitchApiCallType::Auth)
             .with_url("validate")
             .build()?;
-        let response: TokenInfoData = Self::call_api_with_credentials(call, client_id.to_string(), access_token.to_string()).await?;
+        let response: TokenInfoData = Self::call_api_with_credentials(call, client_id.to_string(), access_token).await?;
         Ok(TokenInfo::new(response))
     }
 
-    pub async fn get_me(&mut self) -> Result<User> {
+    pub async fn get_user_info_for_access_token(client_id: impl ToString, access_token: &Secret) -> Result<UserInfo> {
         let call = TwitchApiCall::builder_empty()
-            .with_call_type(TwitchApiCallType::Helix)
-            .with_url("users")
+            .with_call_type(TwitchApiCallType::Auth)
+            .with_url("userinfo")
             .build()?;
-        let mut response: UserResponse = self.call_api(call).await?;
-
-        Ok(response.data.swap_remove(0))
+        Self::call_api_with_credentials(call, client_id.to_string(), access_token).await
     }
 
-    pub async fn get_user_by_login(&mut self, login: impl ToString) -> Result<Option<User>> {
-        let login = login.to_string();
+    pub async fn get_user_info(&self) -> Result<UserInfo> {
         let call = TwitchApiCall::builder_empty()
-            .with_call_type(TwitchApiCallType::Helix)
-            .with_url("users")
-            .with_param("login", login)
+            .with_call_type(TwitchApiCallType::Auth)
+            .with_url("userinfo")
+            .with_scope(Scope::OpenId)
+            .with_token_type(TokenType::User)
             .build()?;
-        let mut response: UserResponse = self.call_api(call).await?;
+        self.call_api(call).await
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+#[derive(Debug)]
+pub struct HttpStatusError {
+    url: String,
+    status: StatusCode,
+    message: String,
+}
+
+impl HttpStatusError {
+    pub fn new(url: impl Into<String>, status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl Error for HttpStatusError {}
+
+impl Display for HttpStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "request to {} failed with status {}", self.url, self.status)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct TokenTypeError {
+    url: String,
+    // None for calls that accept any kind of token
+    required: Option<TokenType>,
+}
+
+impl TokenTypeError {
+    pub fn new(url: impl Into<String>, required: Option<TokenType>) -> Self {
+        Self {
+            url: url.into(),
+            required,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn required(&self) -> Option<TokenType> {
+        self.required
+    }
+}
+
+impl Error for TokenTypeError {}
+
+impl Display for TokenTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.required {
+            Some(required) => write!(f, "request to {} requires a token of type {}, but no provider for one was given", self.url, required),
+            None => write!(f, "request to {} requires a user or app token, but no provider for either was given", self.url)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EmptyResponseError {
+    url: String,
+}
+
+impl EmptyResponseError {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+}
+
+impl Error for EmptyResponseError {}
+
+impl Display for EmptyResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "request to {} returned no data", self.url)
+    }
+}
+
+// the Unknown fallbacks of response enums only stand in for values Twitch sent, so they can't be sent back
+#[derive(Debug)]
+pub struct UnknownValueError {
+    parameter: &'static str,
+}
+
+impl UnknownValueError {
+    pub fn new(parameter: &'static str) -> Self {
+        Self {
+            parameter,
+        }
+    }
+
+    pub fn parameter(&self) -> &str {
+        self.parameter
+    }
+}
+
+impl Error for UnknownValueError {}
+
+impl Display for UnknownValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "an unknown value can not be sent as {}", self.parameter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use futures::future::BoxFuture;
+    use futures::FutureExt;
+    use http::{Response, StatusCode};
+    use hyper::Body;
+    use crate::api::{TwitchApiCall, TwitchApiCallType};
+    use crate::auth::{AccessToken, AuthProvider, MultiUserAuthProvider, Scope, ScopeRequirement, Secret, TokenType};
+    use crate::util::Result;
+    use super::{ApiClient, TokenTypeError};
+
+    // hands out a token named after its type, so the tests can see which provider a call went to
+    struct StubProvider(TokenType);
+
+    impl AuthProvider for StubProvider {
+        fn client_id(&self) -> String {
+            format!("{}-client", self.0)
+        }
+
+        fn current_scopes(&self) -> Vec<Scope> {
+            vec![]
+        }
+
+        fn token_type(&self) -> TokenType {
+            self.0
+        }
+
+        fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+            let token = AccessToken::with_access_token(format!("{}-token", self.0));
+            async move { Ok(token) }.boxed()
+        }
 
-        match response.data.is_empty() {
-            true => Ok(None),
-            false => Ok(Some(response.data.swap_remove(0)))
+        fn access_token_with_scopes(&self, _scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+            self.access_token()
         }
+
+        fn set_access_token(&self, _token: AccessToken) {}
     }
-}
\ No newline at end of file
+
+    async fn credentials(client: &ApiClient, token_type: Option<TokenType>) -> Result<(String, String)> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("users");
+        if let Some(token_type) = token_type {
+            builder = builder.with_token_type(token_type);
+        }
+        let (client_id, token) = client.credentials_for_call(&builder.build()?).await?;
+        Ok((client_id, token.access_token().expose().to_string()))
+    }
+
+    #[tokio::test]
+    async fn routes_calls_by_token_type() {
+        let app_only = ApiClient::new(Arc::new(StubProvider(TokenType::App)));
+        assert_eq!(credentials(&app_only, None).await.unwrap().1, "app-token");
+        let error = credentials(&app_only, Some(TokenType::User)).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<TokenTypeError>().unwrap().required(), Some(TokenType::User));
+
+        let client = app_only
+            .with_auth_provider(Arc::new(StubProvider(TokenType::User)))
+            .with_auth_provider(Arc::new(StubProvider(TokenType::Extension)));
+        assert_eq!(credentials(&client, None).await.unwrap(), ("user-client".to_string(), "user-token".to_string()));
+        assert_eq!(credentials(&client, Some(TokenType::App)).await.unwrap().1, "app-token");
+        assert_eq!(credentials(&client, Some(TokenType::User)).await.unwrap().1, "user-token");
+        assert_eq!(credentials(&client, Some(TokenType::Extension)).await.unwrap().1, "extension-token");
+
+        let extension_only = ApiClient::new(Arc::new(StubProvider(TokenType::Extension)));
+        let error = credentials(&extension_only, None).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<TokenTypeError>().unwrap().required(), None);
+    }
+
+    #[tokio::test]
+    async fn makes_user_calls_as_the_user_context() {
+        let client = ApiClient::new(Arc::new(StubProvider(TokenType::App)))
+            .with_auth_provider(Arc::new(StubProvider(TokenType::User)));
+        assert!(credentials(&client.as_user("141981764"), None).await.is_err());
+
+        let users = MultiUserAuthProvider::new("multi-client", "secret");
+        users.add_user("141981764", AccessToken::with_access_token("multi-token")).await.unwrap();
+        let client = client.with_user_provider(Arc::new(users)).as_user("141981764");
+        assert_eq!(credentials(&client, None).await.unwrap(), ("multi-client".to_string(), "multi-token".to_string()));
+        assert_eq!(credentials(&client, Some(TokenType::User)).await.unwrap().1, "multi-token");
+        // app and extension calls are not made as a user
+        assert_eq!(credentials(&client, Some(TokenType::App)).await.unwrap().1, "app-token");
+        assert!(credentials(&client, Some(TokenType::Extension)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_does_not_leak_secrets() {
+        let client_secret = Secret::from("client-secret-value");
+        let refresh_token = Secret::from("refresh-token-value");
+        let call = ApiClient::refresh_access_token_call("client-id".to_string(), &client_secret, &refresh_token).unwrap();
+        let form = call.form_body().unwrap();
+        assert!(form.contains("refresh_token=refresh-token-value"));
+
+        let response = Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(r#"{"status":400,"message":"Invalid refresh token"}"#))
+            .unwrap();
+        let error = ApiClient::transform_response::<()>(call.full_url().to_string(), response).await.unwrap_err().to_string();
+        assert!(error.contains("Invalid refresh token"));
+        assert!(!error.contains("client-secret-value"));
+        assert!(!error.contains("refresh-token-value"));
+    }
+}
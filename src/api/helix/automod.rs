@@ -0,0 +1,420 @@
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest, MAX_BATCH_SIZE};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AutoModStatus {
+    msg_id: String,
+    is_permitted: bool,
+}
+
+impl AutoModStatus {
+    pub fn msg_id(&self) -> &str {
+        self.msg_id.as_str()
+    }
+
+    // false if AutoMod would hold the message for review
+    pub fn is_permitted(&self) -> bool {
+        self.is_permitted
+    }
+}
+
+#[derive(Serialize)]
+struct AutoModMessage {
+    msg_id: String,
+    msg_text: String,
+}
+
+#[derive(Serialize)]
+struct CheckAutoModStatusBody<'a> {
+    data: &'a [AutoModMessage],
+}
+
+#[derive(Serialize)]
+struct ManageHeldMessageBody {
+    user_id: String,
+    msg_id: String,
+    action: &'static str,
+}
+
+// levels go from 0 (no filtering) to 4 (most aggressive filtering)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AutoModSettings {
+    broadcaster_id: String,
+    moderator_id: String,
+    #[serde(default)]
+    overall_level: Option<u8>,
+    disability: u8,
+    aggression: u8,
+    sexuality_sex_or_gender: u8,
+    misogyny: u8,
+    bullying: u8,
+    swearing: u8,
+    race_ethnicity_or_religion: u8,
+    sex_based_terms: u8,
+}
+
+impl AutoModSettings {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn moderator_id(&self) -> &str {
+        self.moderator_id.as_str()
+    }
+
+    // None if the categories were configured individually
+    pub fn overall_level(&self) -> Option<u8> {
+        self.overall_level
+    }
+
+    pub fn disability(&self) -> u8 {
+        self.disability
+    }
+
+    pub fn aggression(&self) -> u8 {
+        self.aggression
+    }
+
+    pub fn sexuality_sex_or_gender(&self) -> u8 {
+        self.sexuality_sex_or_gender
+    }
+
+    pub fn misogyny(&self) -> u8 {
+        self.misogyny
+    }
+
+    pub fn bullying(&self) -> u8 {
+        self.bullying
+    }
+
+    pub fn swearing(&self) -> u8 {
+        self.swearing
+    }
+
+    pub fn race_ethnicity_or_religion(&self) -> u8 {
+        self.race_ethnicity_or_religion
+    }
+
+    pub fn sex_based_terms(&self) -> u8 {
+        self.sex_based_terms
+    }
+
+    // Twitch derives every category from the overall level
+    pub fn with_overall_level(mut self, level: u8) -> Self {
+        self.overall_level = Some(level);
+        self
+    }
+
+    // setting a single category switches the channel over to individually configured categories
+    pub fn with_disability(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.disability = level;
+        self
+    }
+
+    pub fn with_aggression(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.aggression = level;
+        self
+    }
+
+    pub fn with_sexuality_sex_or_gender(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.sexuality_sex_or_gender = level;
+        self
+    }
+
+    pub fn with_misogyny(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.misogyny = level;
+        self
+    }
+
+    pub fn with_bullying(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.bullying = level;
+        self
+    }
+
+    pub fn with_swearing(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.swearing = level;
+        self
+    }
+
+    pub fn with_race_ethnicity_or_religion(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.race_ethnicity_or_religion = level;
+        self
+    }
+
+    pub fn with_sex_based_terms(mut self, level: u8) -> Self {
+        self.overall_level = None;
+        self.sex_based_terms = level;
+        self
+    }
+}
+
+// PUT replaces all settings, and the categories are ignored whenever an overall level is sent
+#[derive(Serialize)]
+#[serde(untagged)]
+enum AutoModSettingsBody {
+    Overall {
+        overall_level: u8,
+    },
+    Categories {
+        disability: u8,
+        aggression: u8,
+        sexuality_sex_or_gender: u8,
+        misogyny: u8,
+        bullying: u8,
+        swearing: u8,
+        race_ethnicity_or_religion: u8,
+        sex_based_terms: u8,
+    },
+}
+
+impl From<&AutoModSettings> for AutoModSettingsBody {
+    fn from(settings: &AutoModSettings) -> Self {
+        match settings.overall_level {
+            Some(overall_level) => AutoModSettingsBody::Overall {
+                overall_level,
+            },
+            None => AutoModSettingsBody::Categories {
+                disability: settings.disability,
+                aggression: settings.aggression,
+                sexuality_sex_or_gender: settings.sexuality_sex_or_gender,
+                misogyny: settings.misogyny,
+                bullying: settings.bullying,
+                swearing: settings.swearing,
+                race_ethnicity_or_religion: settings.race_ethnicity_or_religion,
+                sex_based_terms: settings.sex_based_terms,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BlockedTerm {
+    broadcaster_id: String,
+    moderator_id: String,
+    id: String,
+    text: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl BlockedTerm {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    // the moderator who added the term
+    pub fn moderator_id(&self) -> &str {
+        self.moderator_id.as_str()
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    // None for terms that never expire
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+}
+
+#[derive(Serialize)]
+struct AddBlockedTermBody {
+    text: String,
+}
+
+impl ApiClient {
+    // takes (message id, message text) pairs; the ids only serve to match the results to the messages
+    pub async fn check_automod_status(
+        &self,
+        broadcaster_id: impl ToString,
+        messages: impl IntoIterator<Item = (impl ToString, impl ToString)>,
+    ) -> Result<Vec<AutoModStatus>> {
+        let broadcaster_id = broadcaster_id.to_string();
+        let messages: Vec<AutoModMessage> = messages.into_iter()
+            .map(|(msg_id, msg_text)| AutoModMessage {
+                msg_id: msg_id.to_string(),
+                msg_text: msg_text.to_string(),
+            })
+            .collect();
+        let mut statuses = Vec::with_capacity(messages.len());
+        for chunk in messages.chunks(MAX_BATCH_SIZE) {
+            let call = TwitchApiCall::<CheckAutoModStatusBody>::builder()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("moderation/enforcements/status")
+                .with_method(Method::POST)
+                .with_param("broadcaster_id", broadcaster_id.as_str())
+                .with_body(CheckAutoModStatusBody {
+                    data: chunk,
+                })
+                .with_scope(Scope::ModerationRead)
+                .with_token_type(TokenType::User)
+                .build()?;
+            let response: HelixResponse<AutoModStatus> = self.call_api(call).await?;
+            statuses.extend(response.data);
+        }
+        Ok(statuses)
+    }
+
+    // releases (allow) or drops (deny) a message AutoMod is holding for review
+    pub async fn manage_held_automod_message(&self, moderator_id: Option<&str>, msg_id: impl ToString, allow: bool) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<ManageHeldMessageBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/automod/message")
+            .with_method(Method::POST)
+            .with_body(ManageHeldMessageBody {
+                user_id: moderator_id,
+                msg_id: msg_id.to_string(),
+                action: match allow {
+                    true => "ALLOW",
+                    false => "DENY"
+                },
+            })
+            .with_scope(Scope::ModeratorManageAutomod)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    pub async fn get_automod_settings(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>) -> Result<AutoModSettings> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/automod/settings")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_any_scope(vec![Scope::ModeratorReadAutomodSettings, Scope::ModeratorManageAutomodSettings])
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.automod_settings_from_response(call).await
+    }
+
+    // usually called with settings from get_automod_settings that were changed through the with_* methods
+    pub async fn update_automod_settings(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, settings: &AutoModSettings) -> Result<AutoModSettings> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<AutoModSettingsBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/automod/settings")
+            .with_method(Method::PUT)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_body(AutoModSettingsBody::from(settings))
+            .with_scope(Scope::ModeratorManageAutomodSettings)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.automod_settings_from_response(call).await
+    }
+
+    async fn automod_settings_from_response<B: serde::Serialize>(&self, call: TwitchApiCall<'_, B>) -> Result<AutoModSettings> {
+        let url = call.full_url().to_string();
+        let response: HelixResponse<AutoModSettings> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    pub async fn get_blocked_terms(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, page: &PageRequest) -> Result<Page<BlockedTerm>> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/blocked_terms")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_any_scope(vec![Scope::ModeratorReadBlockedTerms, Scope::ModeratorManageBlockedTerms])
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<BlockedTerm> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // between 2 and 500 characters, with * as a wildcard
+    pub async fn add_blocked_term(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, text: impl ToString) -> Result<BlockedTerm> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<AddBlockedTermBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/blocked_terms")
+            .with_method(Method::POST)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_body(AddBlockedTermBody {
+                text: text.to_string(),
+            })
+            .with_scope(Scope::ModeratorManageBlockedTerms)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<BlockedTerm> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    pub async fn remove_blocked_term(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, id: impl ToString) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/blocked_terms")
+            .with_method(Method::DELETE)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_param("id", id.to_string())
+            .with_scope(Scope::ModeratorManageBlockedTerms)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AutoModSettings, AutoModSettingsBody};
+
+    #[test]
+    fn sends_either_the_overall_level_or_all_categories() {
+        let settings: AutoModSettings = serde_json::from_str(r#"{
+            "broadcaster_id": "1234",
+            "moderator_id": "5678",
+            "overall_level": 1,
+            "disability": 0,
+            "aggression": 1,
+            "sexuality_sex_or_gender": 0,
+            "misogyny": 0,
+            "bullying": 1,
+            "swearing": 0,
+            "race_ethnicity_or_religion": 1,
+            "sex_based_terms": 0
+        }"#).unwrap();
+        assert_eq!(serde_json::to_string(&AutoModSettingsBody::from(&settings)).unwrap(), r#"{"overall_level":1}"#);
+
+        let settings = settings.with_swearing(3);
+        assert_eq!(settings.overall_level(), None);
+        assert_eq!(
+            serde_json::to_string(&AutoModSettingsBody::from(&settings)).unwrap(),
+            r#"{"disability":0,"aggression":1,"sexuality_sex_or_gender":0,"misogyny":0,"bullying":1,"swearing":3,"race_ethnicity_or_religion":1,"sex_based_terms":0}"#
+        );
+    }
+}
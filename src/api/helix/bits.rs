@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{deserialize_optional_date, HelixResponse};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitsLeaderboardPeriod {
+    Day,
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl BitsLeaderboardPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BitsLeaderboardPeriod::Day => "day",
+            BitsLeaderboardPeriod::Week => "week",
+            BitsLeaderboardPeriod::Month => "month",
+            BitsLeaderboardPeriod::Year => "year",
+            BitsLeaderboardPeriod::All => "all",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BitsLeaderboardQuery {
+    count: Option<u32>,
+    period: Option<BitsLeaderboardPeriod>,
+    started_at: Option<DateTime<Utc>>,
+    user_id: Option<String>,
+}
+
+impl BitsLeaderboardQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 1 to 100, defaults to 10
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn with_period(mut self, period: BitsLeaderboardPeriod) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    // picks which day / week etc. the period refers to; ignored for BitsLeaderboardPeriod::All
+    pub fn with_started_at(mut self, started_at: DateTime<Utc>) -> Self {
+        self.started_at = Some(started_at);
+        self
+    }
+
+    // puts this user's rank in the result, along with the users around them
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BitsLeaderboardEntry {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    rank: u32,
+    score: u64,
+}
+
+impl BitsLeaderboardEntry {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct BitsLeaderboardDateRange {
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    started_at: Option<DateTime<Utc>>,
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BitsLeaderboard {
+    data: Vec<BitsLeaderboardEntry>,
+    date_range: BitsLeaderboardDateRange,
+    total: u32,
+}
+
+impl BitsLeaderboard {
+    pub fn entries(&self) -> &[BitsLeaderboardEntry] {
+        self.data.as_slice()
+    }
+
+    // both None for BitsLeaderboardPeriod::All
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.date_range.started_at
+    }
+
+    pub fn ended_at(&self) -> Option<DateTime<Utc>> {
+        self.date_range.ended_at
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheermoteTheme {
+    Dark,
+    Light,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheermoteFormat {
+    Animated,
+    Static,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheermoteScale {
+    X1,
+    X1_5,
+    X2,
+    X3,
+    X4,
+}
+
+impl CheermoteScale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheermoteScale::X1 => "1",
+            CheermoteScale::X1_5 => "1.5",
+            CheermoteScale::X2 => "2",
+            CheermoteScale::X3 => "3",
+            CheermoteScale::X4 => "4",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CheermoteImageSet {
+    animated: HashMap<String, String>,
+    #[serde(rename = "static")]
+    still: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct CheermoteImages {
+    dark: CheermoteImageSet,
+    light: CheermoteImageSet,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CheermoteTier {
+    id: String,
+    min_bits: u64,
+    color: String,
+    images: CheermoteImages,
+    can_cheer: bool,
+    show_in_bits_card: bool,
+}
+
+impl CheermoteTier {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn min_bits(&self) -> u64 {
+        self.min_bits
+    }
+
+    // hex, e.g. "#9c3ee8"
+    pub fn color(&self) -> &str {
+        self.color.as_str()
+    }
+
+    pub fn image_url(&self, theme: CheermoteTheme, format: CheermoteFormat, scale: CheermoteScale) -> Option<&str> {
+        let images = match theme {
+            CheermoteTheme::Dark => &self.images.dark,
+            CheermoteTheme::Light => &self.images.light,
+        };
+        let images = match format {
+            CheermoteFormat::Animated => &images.animated,
+            CheermoteFormat::Static => &images.still,
+        };
+        images.get(scale.as_str()).map(String::as_str)
+    }
+
+    pub fn can_cheer(&self) -> bool {
+        self.can_cheer
+    }
+
+    pub fn show_in_bits_card(&self) -> bool {
+        self.show_in_bits_card
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Cheermote {
+    prefix: String,
+    tiers: Vec<CheermoteTier>,
+    #[serde(rename = "type")]
+    cheermote_type: String,
+    order: u32,
+    last_updated: DateTime<Utc>,
+    is_charitable: bool,
+}
+
+impl Cheermote {
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_str()
+    }
+
+    pub fn tiers(&self) -> &[CheermoteTier] {
+        self.tiers.as_slice()
+    }
+
+    // global_first_party, global_third_party, channel_custom, display_only or sponsored
+    pub fn cheermote_type(&self) -> &str {
+        self.cheermote_type.as_str()
+    }
+
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+
+    pub fn last_updated(&self) -> DateTime<Utc> {
+        self.last_updated
+    }
+
+    pub fn is_charitable(&self) -> bool {
+        self.is_charitable
+    }
+
+    // the highest tier the amount reaches
+    pub fn tier_for_amount(&self, amount: u64) -> Option<&CheermoteTier> {
+        self.tiers.iter()
+            .filter(|tier| tier.min_bits <= amount)
+            .max_by_key(|tier| tier.min_bits)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResolvedCheer<'a> {
+    cheermote: &'a Cheermote,
+    tier: &'a CheermoteTier,
+    amount: u64,
+}
+
+impl<'a> ResolvedCheer<'a> {
+    pub fn cheermote(&self) -> &'a Cheermote {
+        self.cheermote
+    }
+
+    pub fn tier(&self) -> &'a CheermoteTier {
+        self.tier
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn color(&self) -> &'a str {
+        self.tier.color()
+    }
+
+    pub fn image_url(&self, theme: CheermoteTheme, format: CheermoteFormat, scale: CheermoteScale) -> Option<&'a str> {
+        self.tier.image_url(theme, format, scale)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Cheermotes {
+    cheermotes: Vec<Cheermote>,
+}
+
+impl Cheermotes {
+    pub fn new(cheermotes: Vec<Cheermote>) -> Self {
+        Self {
+            cheermotes,
+        }
+    }
+
+    pub fn all(&self) -> &[Cheermote] {
+        self.cheermotes.as_slice()
+    }
+
+    // prefixes are matched case-insensitively, like they are in chat
+    pub fn get(&self, prefix: &str) -> Option<&Cheermote> {
+        self.cheermotes.iter().find(|cheermote| cheermote.prefix.eq_ignore_ascii_case(prefix))
+    }
+
+    pub fn resolve(&self, prefix: &str, amount: u64) -> Option<ResolvedCheer<'_>> {
+        let cheermote = self.get(prefix)?;
+        let tier = cheermote.tier_for_amount(amount)?;
+        Some(ResolvedCheer {
+            cheermote,
+            tier,
+            amount,
+        })
+    }
+}
+
+impl ApiClient {
+    pub async fn get_bits_leaderboard(&self, query: &BitsLeaderboardQuery) -> Result<BitsLeaderboard> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("bits/leaderboard")
+            .with_scope(Scope::BitsRead)
+            .with_token_type(TokenType::User);
+        if let Some(count) = query.count {
+            builder = builder.with_param("count", count.to_string());
+        }
+        if let Some(period) = query.period {
+            builder = builder.with_param("period", period.as_str());
+        }
+        if let Some(started_at) = query.started_at {
+            builder = builder.with_param("started_at", started_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if let Some(user_id) = &query.user_id {
+            builder = builder.with_param("user_id", user_id.as_str());
+        }
+        self.call_api(builder.build()?).await
+    }
+
+    // without a broadcaster, only the global cheermotes are returned
+    pub async fn get_cheermotes(&self, broadcaster_id: Option<&str>) -> Result<Cheermotes> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("bits/cheermotes");
+        if let Some(broadcaster_id) = broadcaster_id {
+            builder = builder.with_param("broadcaster_id", broadcaster_id.to_string());
+        }
+        let response: HelixResponse<Cheermote> = self.call_api(builder.build()?).await?;
+        Ok(Cheermotes::new(response.data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cheermote, Cheermotes, CheermoteFormat, CheermoteScale, CheermoteTheme};
+
+    fn tier(min_bits: u64, color: &str) -> String {
+        format!(r#"{{
+            "min_bits": {min_bits},
+            "id": "{min_bits}",
+            "color": "{color}",
+            "images": {{
+                "dark": {{
+                    "animated": {{"1": "https://d3aqoihi2n8ty8.cloudfront.net/actions/cheer/dark/animated/{min_bits}/1.gif"}},
+                    "static": {{"1": "https://d3aqoihi2n8ty8.cloudfront.net/actions/cheer/dark/static/{min_bits}/1.png"}}
+                }},
+                "light": {{
+                    "animated": {{"1": "https://d3aqoihi2n8ty8.cloudfront.net/actions/cheer/light/animated/{min_bits}/1.gif"}},
+                    "static": {{"1": "https://d3aqoihi2n8ty8.cloudfront.net/actions/cheer/light/static/{min_bits}/1.png"}}
+                }}
+            }},
+            "can_cheer": true,
+            "show_in_bits_card": true
+        }}"#, min_bits = min_bits, color = color)
+    }
+
+    #[test]
+    fn resolves_the_highest_reached_tier() {
+        let cheer: Cheermote = serde_json::from_str(format!(r#"{{
+            "prefix": "Cheer",
+            "tiers": [{}, {}, {}],
+            "type": "global_first_party",
+            "order": 1,
+            "last_updated": "2018-05-22T00:06:04Z",
+            "is_charitable": false
+        }}"#, tier(1, "#979797"), tier(100, "#9c3ee8"), tier(1000, "#1db2a5")).as_str()).unwrap();
+        let cheermotes = Cheermotes::new(vec![cheer]);
+
+        let resolved = cheermotes.resolve("cheer", 250).unwrap();
+        assert_eq!(resolved.tier().min_bits(), 100);
+        assert_eq!(resolved.color(), "#9c3ee8");
+        assert_eq!(
+            resolved.image_url(CheermoteTheme::Light, CheermoteFormat::Static, CheermoteScale::X1),
+            Some("https://d3aqoihi2n8ty8.cloudfront.net/actions/cheer/light/static/100/1.png")
+        );
+        assert_eq!(resolved.image_url(CheermoteTheme::Dark, CheermoteFormat::Animated, CheermoteScale::X4), None);
+        assert_eq!(cheermotes.resolve("Cheer", 1000).unwrap().tier().min_bits(), 1000);
+        assert!(cheermotes.resolve("Cheer", 0).is_none());
+        assert!(cheermotes.resolve("Kappa", 100).is_none());
+    }
+}
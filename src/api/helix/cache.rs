@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::api::helix::{Game, User};
+
+// users and games rarely change, so lookups by id / login / name are kept around for a while
+pub(crate) const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+// expired entries are swept out every so many inserts, which keeps inserting a batch cheap
+const SWEEP_INTERVAL: usize = 100;
+
+pub(crate) trait CacheEntry: Clone {
+    // every (parameter, value) pair this entry can be looked up by; the first one identifies it
+    fn cache_keys(&self) -> Vec<(&'static str, String)>;
+
+    // lets entries drop data that is specific to the token they were fetched with
+    fn for_cache(&self) -> Self {
+        self.clone()
+    }
+}
+
+struct CacheState<T> {
+    entries: HashMap<String, (Instant, T)>,
+    inserts_since_sweep: usize,
+}
+
+pub(crate) struct LookupCache<T> {
+    ttl: Duration,
+    state: Mutex<CacheState<T>>,
+}
+
+impl<T: CacheEntry> LookupCache<T> {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                inserts_since_sweep: 0,
+            }),
+        }
+    }
+
+    fn cache_key(key: &str, value: &str) -> String {
+        format!("{}:{}", key, value)
+    }
+
+    pub(crate) fn get(&self, key: &str, value: &str) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let cache_key = Self::cache_key(key, value);
+        match state.entries.get(&cache_key) {
+            Some((inserted, entry)) if inserted.elapsed() < self.ttl => Some(entry.clone()),
+            Some(_) => {
+                state.entries.remove(&cache_key);
+                None
+            }
+            None => None
+        }
+    }
+
+    pub(crate) fn insert(&self, entry: &T) {
+        if self.ttl == Duration::from_secs(0) {
+            return;
+        }
+        let cached = entry.for_cache();
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        // entries are otherwise only dropped when looked up again, which many never are
+        state.inserts_since_sweep += 1;
+        if state.inserts_since_sweep >= SWEEP_INTERVAL {
+            let ttl = self.ttl;
+            state.entries.retain(|_, (inserted, _)| now.duration_since(*inserted) < ttl);
+            state.inserts_since_sweep = 0;
+        }
+        for (key, value) in entry.cache_keys() {
+            state.entries.insert(Self::cache_key(key, value.as_str()), (now, cached.clone()));
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+}
+
+pub(crate) struct HelixCache {
+    pub(crate) users: LookupCache<User>,
+    pub(crate) games: LookupCache<Game>,
+}
+
+impl HelixCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            users: LookupCache::new(ttl),
+            games: LookupCache::new(ttl),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{CacheEntry, LookupCache, SWEEP_INTERVAL};
+
+    #[derive(Clone)]
+    struct Entry(usize);
+
+    impl CacheEntry for Entry {
+        fn cache_keys(&self) -> Vec<(&'static str, String)> {
+            vec![("id", self.0.to_string())]
+        }
+    }
+
+    #[test]
+    fn sweeps_expired_entries_every_so_many_inserts() {
+        let cache = LookupCache::new(Duration::from_millis(10));
+        for id in 1..SWEEP_INTERVAL {
+            cache.insert(&Entry(id));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        cache.insert(&Entry(SWEEP_INTERVAL));
+        assert_eq!(cache.state.lock().unwrap().entries.len(), 1);
+        assert!(cache.get("id", SWEEP_INTERVAL.to_string().as_str()).is_some());
+
+        cache.insert(&Entry(0));
+        assert_eq!(cache.state.lock().unwrap().entries.len(), 2);
+    }
+}
@@ -0,0 +1,657 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    time::Duration,
+};
+use chrono::{DateTime, Utc};
+use http::{Method, StatusCode};
+
+use crate::api::{ApiClient, EmptyResponseError, HttpStatusError, TwitchApiCall, TwitchApiCallType, UnknownValueError};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+// the reward and redemption endpoints take at most this many ids at once
+const MAX_REWARD_BATCH_SIZE: usize = 50;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CustomRewardImage {
+    url_1x: String,
+    url_2x: String,
+    url_4x: String,
+}
+
+impl CustomRewardImage {
+    pub fn url_1x(&self) -> &str {
+        self.url_1x.as_str()
+    }
+
+    pub fn url_2x(&self) -> &str {
+        self.url_2x.as_str()
+    }
+
+    pub fn url_4x(&self) -> &str {
+        self.url_4x.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct MaxPerStreamSetting {
+    is_enabled: bool,
+    max_per_stream: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct MaxPerUserPerStreamSetting {
+    is_enabled: bool,
+    max_per_user_per_stream: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct GlobalCooldownSetting {
+    is_enabled: bool,
+    global_cooldown_seconds: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CustomReward {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    id: String,
+    title: String,
+    prompt: String,
+    cost: u64,
+    #[serde(default)]
+    image: Option<CustomRewardImage>,
+    default_image: CustomRewardImage,
+    background_color: String,
+    is_enabled: bool,
+    is_user_input_required: bool,
+    max_per_stream_setting: MaxPerStreamSetting,
+    max_per_user_per_stream_setting: MaxPerUserPerStreamSetting,
+    global_cooldown_setting: GlobalCooldownSetting,
+    is_paused: bool,
+    is_in_stock: bool,
+    should_redemptions_skip_request_queue: bool,
+    #[serde(default)]
+    redemptions_redeemed_current_stream: Option<u64>,
+    #[serde(default)]
+    cooldown_expires_at: Option<DateTime<Utc>>,
+}
+
+impl CustomReward {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn prompt(&self) -> &str {
+        self.prompt.as_str()
+    }
+
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    // falls back to the default image if the broadcaster did not upload one
+    pub fn image(&self) -> &CustomRewardImage {
+        self.image.as_ref().unwrap_or(&self.default_image)
+    }
+
+    pub fn background_color(&self) -> &str {
+        self.background_color.as_str()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    pub fn is_user_input_required(&self) -> bool {
+        self.is_user_input_required
+    }
+
+    pub fn max_per_stream(&self) -> Option<u64> {
+        match self.max_per_stream_setting.is_enabled {
+            true => Some(self.max_per_stream_setting.max_per_stream),
+            false => None
+        }
+    }
+
+    pub fn max_per_user_per_stream(&self) -> Option<u64> {
+        match self.max_per_user_per_stream_setting.is_enabled {
+            true => Some(self.max_per_user_per_stream_setting.max_per_user_per_stream),
+            false => None
+        }
+    }
+
+    pub fn global_cooldown(&self) -> Option<Duration> {
+        match self.global_cooldown_setting.is_enabled {
+            true => Some(Duration::from_secs(self.global_cooldown_setting.global_cooldown_seconds)),
+            false => None
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    pub fn is_in_stock(&self) -> bool {
+        self.is_in_stock
+    }
+
+    pub fn should_redemptions_skip_request_queue(&self) -> bool {
+        self.should_redemptions_skip_request_queue
+    }
+
+    // only known while the channel is live
+    pub fn redemptions_redeemed_current_stream(&self) -> Option<u64> {
+        self.redemptions_redeemed_current_stream
+    }
+
+    pub fn cooldown_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.cooldown_expires_at
+    }
+}
+
+// used both for creating and updating rewards; only the fields that were set are sent
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CustomRewardSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_user_input_required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_max_per_stream_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_per_stream: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_max_per_user_per_stream_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_per_user_per_stream: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_global_cooldown_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    global_cooldown_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_paused: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    should_redemptions_skip_request_queue: Option<bool>,
+}
+
+impl CustomRewardSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_cost(mut self, cost: u64) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn with_enabled(mut self, is_enabled: bool) -> Self {
+        self.is_enabled = Some(is_enabled);
+        self
+    }
+
+    // hex, e.g. "#9147FF"
+    pub fn with_background_color(mut self, background_color: impl Into<String>) -> Self {
+        self.background_color = Some(background_color.into());
+        self
+    }
+
+    pub fn with_user_input_required(mut self, is_user_input_required: bool) -> Self {
+        self.is_user_input_required = Some(is_user_input_required);
+        self
+    }
+
+    // None removes the limit
+    pub fn with_max_per_stream(mut self, max_per_stream: Option<u64>) -> Self {
+        self.is_max_per_stream_enabled = Some(max_per_stream.is_some());
+        self.max_per_stream = max_per_stream;
+        self
+    }
+
+    pub fn with_max_per_user_per_stream(mut self, max_per_user_per_stream: Option<u64>) -> Self {
+        self.is_max_per_user_per_stream_enabled = Some(max_per_user_per_stream.is_some());
+        self.max_per_user_per_stream = max_per_user_per_stream;
+        self
+    }
+
+    // whole seconds, at most 7 days
+    pub fn with_global_cooldown(mut self, global_cooldown: Option<Duration>) -> Self {
+        self.is_global_cooldown_enabled = Some(global_cooldown.is_some());
+        self.global_cooldown_seconds = global_cooldown.map(|cooldown| cooldown.as_secs());
+        self
+    }
+
+    // can only be changed on existing rewards
+    pub fn with_paused(mut self, is_paused: bool) -> Self {
+        self.is_paused = Some(is_paused);
+        self
+    }
+
+    pub fn with_skip_request_queue(mut self, should_redemptions_skip_request_queue: bool) -> Self {
+        self.should_redemptions_skip_request_queue = Some(should_redemptions_skip_request_queue);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RedemptionStatus {
+    Unfulfilled,
+    Fulfilled,
+    Canceled,
+    // only received, sending it fails with an UnknownValueError
+    #[serde(other)]
+    Unknown,
+}
+
+impl RedemptionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RedemptionStatus::Unfulfilled => "UNFULFILLED",
+            RedemptionStatus::Fulfilled => "FULFILLED",
+            RedemptionStatus::Canceled => "CANCELED",
+            RedemptionStatus::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedemptionSort {
+    Oldest,
+    Newest,
+}
+
+impl RedemptionSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RedemptionSort::Oldest => "OLDEST",
+            RedemptionSort::Newest => "NEWEST",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RedemptionReward {
+    id: String,
+    title: String,
+    prompt: String,
+    cost: u64,
+}
+
+impl RedemptionReward {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn prompt(&self) -> &str {
+        self.prompt.as_str()
+    }
+
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CustomRewardRedemption {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    id: String,
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    user_input: String,
+    status: RedemptionStatus,
+    redeemed_at: DateTime<Utc>,
+    reward: RedemptionReward,
+}
+
+impl CustomRewardRedemption {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn user_input(&self) -> &str {
+        self.user_input.as_str()
+    }
+
+    pub fn status(&self) -> RedemptionStatus {
+        self.status
+    }
+
+    pub fn redeemed_at(&self) -> DateTime<Utc> {
+        self.redeemed_at
+    }
+
+    pub fn reward(&self) -> &RedemptionReward {
+        &self.reward
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RedemptionFilter {
+    reward_id: String,
+    status: Option<RedemptionStatus>,
+    ids: Vec<String>,
+    sort: Option<RedemptionSort>,
+}
+
+impl RedemptionFilter {
+    // without ids, a status is required as well
+    pub fn new(reward_id: impl Into<String>) -> Self {
+        Self {
+            reward_id: reward_id.into(),
+            status: None,
+            ids: vec![],
+            sort: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: RedemptionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    // up to 50 ids
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.ids.push(id.into());
+        self
+    }
+
+    pub fn with_sort(mut self, sort: RedemptionSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct RedemptionStatusUpdate {
+    status: RedemptionStatus,
+}
+
+// Twitch only lets apps manage rewards (and their redemptions) that were created with the same client id
+#[derive(Debug)]
+pub struct RewardNotManageableError {
+    url: String,
+    message: String,
+}
+
+impl RewardNotManageableError {
+    pub fn new(url: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl Error for RewardNotManageableError {}
+
+impl Display for RewardNotManageableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "request to {} failed because the reward was not created by this client id", self.url)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+// Twitch also answers 403 for broadcasters without channel points or tokens of other users, so the
+// status and endpoint alone don't tell the reward's owner apart; the client id mismatch is the only
+// documented 403 of these endpoints whose message mentions the client id, which is matched loosely
+// ("Client-Id", "client ID") so rewording the rest of the message does not break it
+fn map_reward_error(e: Box<dyn Error + Send + Sync>) -> Box<dyn Error + Send + Sync> {
+    match e.downcast_ref::<HttpStatusError>() {
+        Some(error) if error.status() == StatusCode::FORBIDDEN && mentions_client_id(error.message()) => {
+            Box::new(RewardNotManageableError::new(error.url(), error.message()))
+        }
+        _ => e
+    }
+}
+
+fn mentions_client_id(message: &str) -> bool {
+    message.to_lowercase().replace(['-', '_'], " ").contains("client id")
+}
+
+impl ApiClient {
+    pub async fn create_custom_reward(&self, broadcaster_id: impl ToString, title: impl Into<String>, cost: u64, settings: CustomRewardSettings) -> Result<CustomReward> {
+        let call = TwitchApiCall::<CustomRewardSettings>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channel_points/custom_rewards")
+            .with_method(Method::POST)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_body(settings.with_title(title).with_cost(cost))
+            .with_scope(Scope::ChannelManageRedemptions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<CustomReward> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    pub async fn update_custom_reward(&self, broadcaster_id: impl ToString, reward_id: impl ToString, settings: CustomRewardSettings) -> Result<CustomReward> {
+        let call = TwitchApiCall::<CustomRewardSettings>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channel_points/custom_rewards")
+            .with_method(Method::PATCH)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("id", reward_id.to_string())
+            .with_body(settings)
+            .with_scope(Scope::ChannelManageRedemptions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<CustomReward> = self.call_api(call).await.map_err(map_reward_error)?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    pub async fn delete_custom_reward(&self, broadcaster_id: impl ToString, reward_id: impl ToString) -> Result<()> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channel_points/custom_rewards")
+            .with_method(Method::DELETE)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("id", reward_id.to_string())
+            .with_scope(Scope::ChannelManageRedemptions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await.map_err(map_reward_error)
+    }
+
+    // all of the broadcaster's rewards if no ids are given
+    pub async fn get_custom_rewards(&self, broadcaster_id: impl ToString, ids: impl IntoIterator<Item = impl ToString>, only_manageable_rewards: bool) -> Result<Vec<CustomReward>> {
+        let broadcaster_id = broadcaster_id.to_string();
+        let ids: Vec<String> = ids.into_iter().map(|id| id.to_string()).collect();
+        let mut chunks: Vec<&[String]> = ids.chunks(MAX_REWARD_BATCH_SIZE).collect();
+        if chunks.is_empty() {
+            chunks.push(&[]);
+        }
+        let mut rewards = Vec::with_capacity(ids.len());
+        for chunk in chunks {
+            let builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("channel_points/custom_rewards")
+                .with_param("broadcaster_id", broadcaster_id.as_str())
+                .with_param("only_manageable_rewards", only_manageable_rewards.to_string())
+                .with_any_scope(vec![Scope::ChannelReadRedemptions, Scope::ChannelManageRedemptions])
+                .with_token_type(TokenType::User);
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("id", id.as_str()));
+            let response: HelixResponse<CustomReward> = self.call_api(builder.build()?).await?;
+            rewards.extend(response.data);
+        }
+        Ok(rewards)
+    }
+
+    // only works for rewards created by this client id
+    pub async fn get_custom_reward_redemptions(&self, broadcaster_id: impl ToString, filter: &RedemptionFilter, page: &PageRequest) -> Result<Page<CustomRewardRedemption>> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channel_points/custom_rewards/redemptions")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("reward_id", filter.reward_id.as_str())
+            .with_any_scope(vec![Scope::ChannelReadRedemptions, Scope::ChannelManageRedemptions])
+            .with_token_type(TokenType::User);
+        if let Some(status) = filter.status {
+            if status == RedemptionStatus::Unknown {
+                return Err(Box::new(UnknownValueError::new("status")));
+            }
+            builder = builder.with_param("status", status.as_str());
+        }
+        for id in &filter.ids {
+            builder = builder.with_param("id", id.as_str());
+        }
+        if let Some(sort) = filter.sort {
+            builder = builder.with_param("sort", sort.as_str());
+        }
+        let response: HelixPaginatedResponse<CustomRewardRedemption> = self.call_api(page.apply(builder).build()?).await.map_err(map_reward_error)?;
+        Ok(Page::new(response, page))
+    }
+
+    // fulfils or cancels the given redemptions of one reward; only unfulfilled redemptions can be updated
+    pub async fn update_redemption_status(
+        &self,
+        broadcaster_id: impl ToString,
+        reward_id: impl ToString,
+        redemption_ids: impl IntoIterator<Item = impl ToString>,
+        status: RedemptionStatus,
+    ) -> Result<Vec<CustomRewardRedemption>> {
+        if status == RedemptionStatus::Unknown {
+            return Err(Box::new(UnknownValueError::new("status")));
+        }
+        let broadcaster_id = broadcaster_id.to_string();
+        let reward_id = reward_id.to_string();
+        let redemption_ids: Vec<String> = redemption_ids.into_iter().map(|id| id.to_string()).collect();
+        let mut redemptions = Vec::with_capacity(redemption_ids.len());
+        for chunk in redemption_ids.chunks(MAX_REWARD_BATCH_SIZE) {
+            let builder = TwitchApiCall::<RedemptionStatusUpdate>::builder()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("channel_points/custom_rewards/redemptions")
+                .with_method(Method::PATCH)
+                .with_param("broadcaster_id", broadcaster_id.as_str())
+                .with_param("reward_id", reward_id.as_str())
+                .with_body(RedemptionStatusUpdate {
+                    status,
+                })
+                .with_scope(Scope::ChannelManageRedemptions)
+                .with_token_type(TokenType::User);
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("id", id.as_str()));
+            let response: HelixResponse<CustomRewardRedemption> = self.call_api(builder.build()?).await.map_err(map_reward_error)?;
+            redemptions.extend(response.data);
+        }
+        Ok(redemptions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use http::StatusCode;
+    use crate::api::HttpStatusError;
+    use super::{map_reward_error, CustomRewardSettings, RedemptionStatus, RewardNotManageableError};
+
+    #[test]
+    fn serializes_limits_with_their_switches() {
+        let settings = CustomRewardSettings::new()
+            .with_max_per_stream(Some(3))
+            .with_global_cooldown(None)
+            .with_global_cooldown(Some(Duration::from_secs(90)));
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap(),
+            r#"{"is_max_per_stream_enabled":true,"max_per_stream":3,"is_global_cooldown_enabled":true,"global_cooldown_seconds":90}"#
+        );
+    }
+
+    #[test]
+    fn distinguishes_rewards_of_other_client_ids() {
+        let url = "https://api.twitch.tv/helix/channel_points/custom_rewards";
+        let forbidden = map_reward_error(Box::new(HttpStatusError::new(url, StatusCode::FORBIDDEN, "The ID in the Client-Id header must match the client ID used to create the custom reward.")));
+        assert!(forbidden.downcast_ref::<RewardNotManageableError>().is_some());
+        let not_affiliate = map_reward_error(Box::new(HttpStatusError::new(url, StatusCode::FORBIDDEN, "The broadcaster must be a partner or affiliate.")));
+        assert!(not_affiliate.downcast_ref::<HttpStatusError>().is_some());
+        let not_found = map_reward_error(Box::new(HttpStatusError::new(url, StatusCode::NOT_FOUND, "not found")));
+        assert!(not_found.downcast_ref::<HttpStatusError>().is_some());
+    }
+
+    #[test]
+    fn accepts_unknown_redemption_statuses() {
+        assert_eq!(serde_json::from_str::<RedemptionStatus>(r#""FULFILLED""#).unwrap(), RedemptionStatus::Fulfilled);
+        assert_eq!(serde_json::from_str::<RedemptionStatus>(r#""REFUNDED""#).unwrap(), RedemptionStatus::Unknown);
+    }
+}
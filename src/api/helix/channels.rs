@@ -0,0 +1,257 @@
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType, UnknownValueError};
+use crate::api::helix::{HelixResponse, MAX_BATCH_SIZE};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentClassificationLabel {
+    DebatedSocialIssuesAndPolitics,
+    DrugsIntoxication,
+    SexualThemes,
+    ViolentGraphic,
+    Gambling,
+    ProfanityVulgarity,
+    // labels that are set automatically (e.g. "MatureGame") or newer than this library;
+    // updating it fails with an UnknownValueError
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChannelInformation {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    broadcaster_language: String,
+    game_id: String,
+    game_name: String,
+    title: String,
+    delay: u32,
+    tags: Vec<String>,
+    content_classification_labels: Vec<ContentClassificationLabel>,
+    is_branded_content: bool,
+}
+
+impl ChannelInformation {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn broadcaster_language(&self) -> &str {
+        self.broadcaster_language.as_str()
+    }
+
+    // empty if no category was ever set
+    pub fn game_id(&self) -> &str {
+        self.game_id.as_str()
+    }
+
+    pub fn game_name(&self) -> &str {
+        self.game_name.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    // in seconds; only visible to the broadcaster themselves, 0 for everyone else
+    pub fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    pub fn tags(&self) -> &[String] {
+        self.tags.as_slice()
+    }
+
+    pub fn content_classification_labels(&self) -> &[ContentClassificationLabel] {
+        self.content_classification_labels.as_slice()
+    }
+
+    pub fn is_branded_content(&self) -> bool {
+        self.is_branded_content
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct ContentClassificationLabelUpdate {
+    id: ContentClassificationLabel,
+    is_enabled: bool,
+}
+
+// only the fields that were set are sent, everything else stays as it is
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ChannelUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    broadcaster_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    content_classification_labels: Vec<ContentClassificationLabelUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_branded_content: Option<bool>,
+}
+
+impl ChannelUpdate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    // "0" or "" removes the category
+    pub fn with_game_id(mut self, game_id: impl Into<String>) -> Self {
+        self.game_id = Some(game_id.into());
+        self
+    }
+
+    pub fn with_broadcaster_language(mut self, language: impl Into<String>) -> Self {
+        self.broadcaster_language = Some(language.into());
+        self
+    }
+
+    // partners only, up to 900 seconds
+    pub fn with_delay(mut self, delay: u32) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    // replaces all tags; an empty list removes them
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = Some(tags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_content_classification_label(mut self, label: ContentClassificationLabel, is_enabled: bool) -> Self {
+        self.content_classification_labels.retain(|update| update.id != label);
+        self.content_classification_labels.push(ContentClassificationLabelUpdate {
+            id: label,
+            is_enabled,
+        });
+        self
+    }
+
+    pub fn with_branded_content(mut self, is_branded_content: bool) -> Self {
+        self.is_branded_content = Some(is_branded_content);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.game_id.is_none()
+            && self.broadcaster_language.is_none()
+            && self.delay.is_none()
+            && self.tags.is_none()
+            && self.content_classification_labels.is_empty()
+            && self.is_branded_content.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChannelEditor {
+    user_id: String,
+    user_name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl ChannelEditor {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl ApiClient {
+    pub async fn get_channel_information(&self, broadcaster_id: impl ToString) -> Result<Option<ChannelInformation>> {
+        Ok(self.get_channels_information(vec![broadcaster_id.to_string()]).await?.into_iter().next())
+    }
+
+    pub async fn get_channels_information(&self, broadcaster_ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<ChannelInformation>> {
+        let broadcaster_ids: Vec<String> = broadcaster_ids.into_iter().map(|id| id.to_string()).collect();
+        let mut channels = Vec::with_capacity(broadcaster_ids.len());
+        for chunk in broadcaster_ids.chunks(MAX_BATCH_SIZE) {
+            let mut builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("channels");
+            for broadcaster_id in chunk {
+                builder = builder.with_param("broadcaster_id", broadcaster_id.as_str());
+            }
+            let response: HelixResponse<ChannelInformation> = self.call_api(builder.build()?).await?;
+            channels.extend(response.data);
+        }
+        Ok(channels)
+    }
+
+    pub async fn modify_channel_information(&self, broadcaster_id: impl ToString, update: ChannelUpdate) -> Result<()> {
+        if update.content_classification_labels.iter().any(|label| label.id == ContentClassificationLabel::Unknown) {
+            return Err(Box::new(UnknownValueError::new("content_classification_labels")));
+        }
+        let call = TwitchApiCall::<ChannelUpdate>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels")
+            .with_method(Method::PATCH)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_body(update)
+            .with_scope(Scope::ChannelManageBroadcast)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    pub async fn get_channel_editors(&self, broadcaster_id: impl ToString) -> Result<Vec<ChannelEditor>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/editors")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_scope(Scope::ChannelReadEditors)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let response: HelixResponse<ChannelEditor> = self.call_api(call).await?;
+        Ok(response.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelUpdate, ContentClassificationLabel};
+
+    #[test]
+    fn serializes_only_changed_fields() {
+        let update = ChannelUpdate::new()
+            .with_title("speedrunning all day")
+            .with_tags(vec!["English", "Speedrun"])
+            .with_content_classification_label(ContentClassificationLabel::Gambling, true)
+            .with_content_classification_label(ContentClassificationLabel::Gambling, false);
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"title":"speedrunning all day","tags":["English","Speedrun"],"content_classification_labels":[{"id":"Gambling","is_enabled":false}]}"#
+        );
+        assert!(ChannelUpdate::new().is_empty());
+    }
+}
@@ -0,0 +1,671 @@
+use std::time::Duration;
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest, MAX_BATCH_SIZE};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+// Get Emote Set accepts fewer ids than the other batch endpoints
+const MAX_EMOTE_SET_BATCH_SIZE: usize = 25;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Chatter {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+}
+
+impl Chatter {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmoteFormat {
+    Static,
+    Animated,
+    // formats, themes and scales Twitch added later; there are no URLs for them
+    #[serde(other)]
+    Unknown,
+}
+
+impl EmoteFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmoteFormat::Static => "static",
+            EmoteFormat::Animated => "animated",
+            EmoteFormat::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmoteTheme {
+    Light,
+    Dark,
+    #[serde(other)]
+    Unknown,
+}
+
+impl EmoteTheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmoteTheme::Light => "light",
+            EmoteTheme::Dark => "dark",
+            EmoteTheme::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum EmoteScale {
+    #[serde(rename = "1.0")]
+    X1,
+    #[serde(rename = "2.0")]
+    X2,
+    #[serde(rename = "3.0")]
+    X3,
+    #[serde(other)]
+    Unknown,
+}
+
+impl EmoteScale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmoteScale::X1 => "1.0",
+            EmoteScale::X2 => "2.0",
+            EmoteScale::X3 => "3.0",
+            EmoteScale::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Emote {
+    id: String,
+    name: String,
+    #[serde(default)]
+    tier: Option<String>,
+    #[serde(default)]
+    emote_type: Option<String>,
+    #[serde(default)]
+    emote_set_id: Option<String>,
+    #[serde(default)]
+    owner_id: Option<String>,
+    format: Vec<EmoteFormat>,
+    scale: Vec<EmoteScale>,
+    theme_mode: Vec<EmoteTheme>,
+    // every emote endpoint sends the URL template once next to the data
+    #[serde(skip)]
+    template: String,
+}
+
+impl Emote {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // the subscriber tier ("1000", "2000" or "3000") for subscriber emotes
+    pub fn tier(&self) -> Option<&str> {
+        self.tier.as_deref().filter(|tier| !tier.is_empty())
+    }
+
+    // e.g. "subscriptions", "bitstier", "follower" or "globals"; not reported by the global emotes endpoint
+    pub fn emote_type(&self) -> Option<&str> {
+        self.emote_type.as_deref()
+    }
+
+    pub fn emote_set_id(&self) -> Option<&str> {
+        self.emote_set_id.as_deref()
+    }
+
+    pub fn owner_id(&self) -> Option<&str> {
+        self.owner_id.as_deref()
+    }
+
+    pub fn formats(&self) -> &[EmoteFormat] {
+        self.format.as_slice()
+    }
+
+    pub fn scales(&self) -> &[EmoteScale] {
+        self.scale.as_slice()
+    }
+
+    pub fn themes(&self) -> &[EmoteTheme] {
+        self.theme_mode.as_slice()
+    }
+
+    // check formats() before asking for an animated URL, static emotes have none; unknown values have no URL
+    pub fn url(&self, format: EmoteFormat, theme: EmoteTheme, scale: EmoteScale) -> Option<String> {
+        if format == EmoteFormat::Unknown || theme == EmoteTheme::Unknown || scale == EmoteScale::Unknown {
+            return None;
+        }
+        Some(self.template
+            .replace("{{id}}", self.id.as_str())
+            .replace("{{format}}", format.as_str())
+            .replace("{{theme_mode}}", theme.as_str())
+            .replace("{{scale}}", scale.as_str()))
+    }
+}
+
+#[derive(Deserialize)]
+struct EmoteResponse {
+    #[serde(flatten)]
+    response: HelixPaginatedResponse<Emote>,
+    template: String,
+}
+
+impl EmoteResponse {
+    fn into_response(mut self) -> HelixPaginatedResponse<Emote> {
+        for emote in &mut self.response.data {
+            emote.template = self.template.clone();
+        }
+        self.response
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChatBadge {
+    id: String,
+    image_url_1x: String,
+    image_url_2x: String,
+    image_url_4x: String,
+    title: String,
+    description: String,
+    #[serde(default)]
+    click_action: Option<String>,
+    #[serde(default)]
+    click_url: Option<String>,
+}
+
+impl ChatBadge {
+    // the version, e.g. the number of months for subscriber badges
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn image_url_1x(&self) -> &str {
+        self.image_url_1x.as_str()
+    }
+
+    pub fn image_url_2x(&self) -> &str {
+        self.image_url_2x.as_str()
+    }
+
+    pub fn image_url_4x(&self) -> &str {
+        self.image_url_4x.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn click_action(&self) -> Option<&str> {
+        self.click_action.as_deref()
+    }
+
+    pub fn click_url(&self) -> Option<&str> {
+        self.click_url.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChatBadgeSet {
+    set_id: String,
+    versions: Vec<ChatBadge>,
+}
+
+impl ChatBadgeSet {
+    pub fn set_id(&self) -> &str {
+        self.set_id.as_str()
+    }
+
+    pub fn versions(&self) -> &[ChatBadge] {
+        self.versions.as_slice()
+    }
+
+    pub fn version(&self, id: &str) -> Option<&ChatBadge> {
+        self.versions.iter().find(|badge| badge.id == id)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChatSettings {
+    broadcaster_id: String,
+    emote_mode: bool,
+    follower_mode: bool,
+    #[serde(default)]
+    follower_mode_duration: Option<u64>,
+    #[serde(default)]
+    moderator_id: Option<String>,
+    #[serde(default)]
+    non_moderator_chat_delay: Option<bool>,
+    #[serde(default)]
+    non_moderator_chat_delay_duration: Option<u64>,
+    slow_mode: bool,
+    #[serde(default)]
+    slow_mode_wait_time: Option<u64>,
+    subscriber_mode: bool,
+    unique_chat_mode: bool,
+}
+
+impl ChatSettings {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn moderator_id(&self) -> Option<&str> {
+        self.moderator_id.as_deref()
+    }
+
+    pub fn emote_mode(&self) -> bool {
+        self.emote_mode
+    }
+
+    // how long users have to follow before they can chat, None if follower mode is off
+    pub fn follower_mode(&self) -> Option<Duration> {
+        match self.follower_mode {
+            true => Some(Duration::from_secs(self.follower_mode_duration.unwrap_or(0) * 60)),
+            false => None
+        }
+    }
+
+    // only known when the settings were requested by a moderator; Some(None) if the delay is off
+    pub fn non_moderator_chat_delay(&self) -> Option<Option<Duration>> {
+        self.non_moderator_chat_delay.map(|enabled| match enabled {
+            true => Some(Duration::from_secs(self.non_moderator_chat_delay_duration.unwrap_or(0))),
+            false => None
+        })
+    }
+
+    // how long users have to wait between messages, None if slow mode is off
+    pub fn slow_mode(&self) -> Option<Duration> {
+        match self.slow_mode {
+            true => Some(Duration::from_secs(self.slow_mode_wait_time.unwrap_or(0))),
+            false => None
+        }
+    }
+
+    pub fn subscriber_mode(&self) -> bool {
+        self.subscriber_mode
+    }
+
+    pub fn unique_chat_mode(&self) -> bool {
+        self.unique_chat_mode
+    }
+}
+
+// only the settings that were set are changed
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ChatSettingsUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    emote_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follower_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    follower_mode_duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    non_moderator_chat_delay: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    non_moderator_chat_delay_duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slow_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slow_mode_wait_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subscriber_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique_chat_mode: Option<bool>,
+}
+
+impl ChatSettingsUpdate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_emote_mode(mut self, emote_mode: bool) -> Self {
+        self.emote_mode = Some(emote_mode);
+        self
+    }
+
+    // whole minutes, up to 3 months; None turns follower mode off
+    pub fn with_follower_mode(mut self, follow_duration: Option<Duration>) -> Self {
+        self.follower_mode = Some(follow_duration.is_some());
+        self.follower_mode_duration = follow_duration.map(|duration| duration.as_secs() / 60);
+        self
+    }
+
+    // 2, 4 or 6 seconds; None turns the delay off
+    pub fn with_non_moderator_chat_delay(mut self, delay: Option<Duration>) -> Self {
+        self.non_moderator_chat_delay = Some(delay.is_some());
+        self.non_moderator_chat_delay_duration = delay.map(|delay| delay.as_secs());
+        self
+    }
+
+    // between 3 and 120 seconds; None turns slow mode off
+    pub fn with_slow_mode(mut self, wait_time: Option<Duration>) -> Self {
+        self.slow_mode = Some(wait_time.is_some());
+        self.slow_mode_wait_time = wait_time.map(|wait_time| wait_time.as_secs());
+        self
+    }
+
+    pub fn with_subscriber_mode(mut self, subscriber_mode: bool) -> Self {
+        self.subscriber_mode = Some(subscriber_mode);
+        self
+    }
+
+    pub fn with_unique_chat_mode(mut self, unique_chat_mode: bool) -> Self {
+        self.unique_chat_mode = Some(unique_chat_mode);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementColor {
+    // the channel's accent color
+    Primary,
+    Blue,
+    Green,
+    Orange,
+    Purple,
+}
+
+#[derive(Serialize)]
+struct SendAnnouncementBody {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<AnnouncementColor>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserChatColor {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    color: String,
+}
+
+impl UserChatColor {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    // hex, None if the user never picked a color
+    pub fn color(&self) -> Option<&str> {
+        Some(self.color.as_str()).filter(|color| !color.is_empty())
+    }
+}
+
+impl ApiClient {
+    // up to 1000 chatters per page; the page's total is the number of users in chat
+    pub async fn get_chatters(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, page: &PageRequest) -> Result<Page<Chatter>> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/chatters")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_scope(Scope::ModeratorReadChatters)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<Chatter> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // the broadcaster's subscriber, Bits tier and follower emotes
+    pub async fn get_channel_emotes(&self, broadcaster_id: impl ToString) -> Result<Vec<Emote>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/emotes")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .build()?;
+        let response: EmoteResponse = self.call_api(call).await?;
+        Ok(response.into_response().data)
+    }
+
+    pub async fn get_global_emotes(&self) -> Result<Vec<Emote>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/emotes/global")
+            .build()?;
+        let response: EmoteResponse = self.call_api(call).await?;
+        Ok(response.into_response().data)
+    }
+
+    pub async fn get_emote_sets(&self, emote_set_ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<Emote>> {
+        let emote_set_ids: Vec<String> = emote_set_ids.into_iter().map(|id| id.to_string()).collect();
+        let mut emotes = vec![];
+        for chunk in emote_set_ids.chunks(MAX_EMOTE_SET_BATCH_SIZE) {
+            let builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("chat/emotes/set");
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("emote_set_id", id.as_str()));
+            let response: EmoteResponse = self.call_api(builder.build()?).await?;
+            emotes.extend(response.into_response().data);
+        }
+        Ok(emotes)
+    }
+
+    // every emote the user can use; with a broadcaster id, also the follower emotes of that channel
+    pub async fn get_user_emotes(&self, user_id: impl ToString, broadcaster_id: Option<&str>, page: &PageRequest) -> Result<Page<Emote>> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/emotes/user")
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::UserReadEmotes)
+            .with_token_type(TokenType::User);
+        if let Some(broadcaster_id) = broadcaster_id {
+            builder = builder.with_param("broadcaster_id", broadcaster_id);
+        }
+        let response: EmoteResponse = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response.into_response(), page))
+    }
+
+    pub async fn get_channel_chat_badges(&self, broadcaster_id: impl ToString) -> Result<Vec<ChatBadgeSet>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/badges")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .build()?;
+        let response: HelixResponse<ChatBadgeSet> = self.call_api(call).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_global_chat_badges(&self) -> Result<Vec<ChatBadgeSet>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/badges/global")
+            .build()?;
+        let response: HelixResponse<ChatBadgeSet> = self.call_api(call).await?;
+        Ok(response.data)
+    }
+
+    // the non-moderator chat delay is only included when asking as a moderator of the channel
+    pub async fn get_chat_settings(&self, broadcaster_id: impl ToString, as_moderator: bool) -> Result<ChatSettings> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/settings")
+            .with_param("broadcaster_id", broadcaster_id.to_string());
+        if as_moderator {
+            builder = builder
+                .with_param("moderator_id", self.token_user_id().await?)
+                .with_token_type(TokenType::User);
+        }
+        self.chat_settings_from_response(builder.build()?).await
+    }
+
+    pub async fn update_chat_settings(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, update: ChatSettingsUpdate) -> Result<ChatSettings> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<ChatSettingsUpdate>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/settings")
+            .with_method(Method::PATCH)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_body(update)
+            .with_scope(Scope::ModeratorManageChatSettings)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.chat_settings_from_response(call).await
+    }
+
+    async fn chat_settings_from_response<B: serde::Serialize>(&self, call: TwitchApiCall<'_, B>) -> Result<ChatSettings> {
+        let url = call.full_url().to_string();
+        let response: HelixResponse<ChatSettings> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    // without a color, Twitch uses the channel's accent color
+    pub async fn send_chat_announcement(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, message: impl ToString, color: Option<AnnouncementColor>) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<SendAnnouncementBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/announcements")
+            .with_method(Method::POST)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_body(SendAnnouncementBody {
+                message: message.to_string(),
+                color,
+            })
+            .with_scope(Scope::ModeratorManageAnnouncements)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    // the broadcaster has to be live, and Twitch rate limits shoutouts per channel and per target
+    pub async fn send_shoutout(&self, from_broadcaster_id: impl ToString, to_broadcaster_id: impl ToString, moderator_id: Option<&str>) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/shoutouts")
+            .with_method(Method::POST)
+            .with_param("from_broadcaster_id", from_broadcaster_id.to_string())
+            .with_param("to_broadcaster_id", to_broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_scope(Scope::ModeratorManageShoutouts)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    pub async fn get_user_chat_colors(&self, user_ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<UserChatColor>> {
+        let user_ids: Vec<String> = user_ids.into_iter().map(|id| id.to_string()).collect();
+        let mut colors = Vec::with_capacity(user_ids.len());
+        for chunk in user_ids.chunks(MAX_BATCH_SIZE) {
+            let builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("chat/color");
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("user_id", id.as_str()));
+            let response: HelixResponse<UserChatColor> = self.call_api(builder.build()?).await?;
+            colors.extend(response.data);
+        }
+        Ok(colors)
+    }
+
+    // a named color like "blue_violet" or "dodger_blue"; Turbo and Prime users may also use hex colors like "#9146FF"
+    pub async fn update_user_chat_color(&self, user_id: impl ToString, color: impl ToString) -> Result<()> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("chat/color")
+            .with_method(Method::PUT)
+            .with_param("user_id", user_id.to_string())
+            .with_param("color", color.to_string())
+            .with_scope(Scope::UserManageChatColor)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    // messages older than 6 hours can not be deleted
+    pub async fn delete_chat_message(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, message_id: impl ToString) -> Result<()> {
+        self.delete_chat_messages(broadcaster_id.to_string(), moderator_id, Some(message_id.to_string())).await
+    }
+
+    pub async fn clear_chat(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>) -> Result<()> {
+        self.delete_chat_messages(broadcaster_id.to_string(), moderator_id, None).await
+    }
+
+    async fn delete_chat_messages(&self, broadcaster_id: String, moderator_id: Option<&str>, message_id: Option<String>) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/chat")
+            .with_method(Method::DELETE)
+            .with_param("broadcaster_id", broadcaster_id)
+            .with_param("moderator_id", moderator_id)
+            .with_scope(Scope::ModeratorManageChatMessages)
+            .with_token_type(TokenType::User);
+        if let Some(message_id) = message_id {
+            builder = builder.with_param("message_id", message_id);
+        }
+        self.call_api(builder.build()?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmoteFormat, EmoteResponse, EmoteScale, EmoteTheme};
+
+    #[test]
+    fn templates_emote_urls() {
+        let response: EmoteResponse = serde_json::from_str(r#"{
+            "data": [{
+                "id": "304456832",
+                "name": "twitchdevPitchfork",
+                "images": {
+                    "url_1x": "https://static-cdn.jtvnw.net/emoticons/v2/304456832/static/light/1.0",
+                    "url_2x": "https://static-cdn.jtvnw.net/emoticons/v2/304456832/static/light/2.0",
+                    "url_4x": "https://static-cdn.jtvnw.net/emoticons/v2/304456832/static/light/3.0"
+                },
+                "tier": "1000",
+                "emote_type": "subscriptions",
+                "emote_set_id": "301590448",
+                "format": ["static"],
+                "scale": ["1.0", "2.0", "3.0", "4.0"],
+                "theme_mode": ["light", "dark"]
+            }],
+            "template": "https://static-cdn.jtvnw.net/emoticons/v2/{{id}}/{{format}}/{{theme_mode}}/{{scale}}"
+        }"#).unwrap();
+        let emotes = response.into_response().data;
+        let emote = &emotes[0];
+        assert_eq!(emote.tier(), Some("1000"));
+        assert_eq!(emote.formats(), &[EmoteFormat::Static]);
+        assert_eq!(
+            emote.url(EmoteFormat::Static, EmoteTheme::Dark, EmoteScale::X3).unwrap(),
+            "https://static-cdn.jtvnw.net/emoticons/v2/304456832/static/dark/3.0"
+        );
+        assert_eq!(emote.scales().last(), Some(&EmoteScale::Unknown));
+        assert_eq!(emote.url(EmoteFormat::Static, EmoteTheme::Dark, EmoteScale::Unknown), None);
+    }
+}
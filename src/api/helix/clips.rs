@@ -0,0 +1,317 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    time::Duration,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use http::Method;
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+// Twitch recommends giving up on a clip that is not there after 15 seconds
+pub const DEFAULT_CLIP_TIMEOUT: Duration = Duration::from_secs(15);
+const CLIP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreatedClip {
+    id: String,
+    edit_url: String,
+}
+
+impl CreatedClip {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn edit_url(&self) -> &str {
+        self.edit_url.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Clip {
+    id: String,
+    url: String,
+    embed_url: String,
+    broadcaster_id: String,
+    broadcaster_name: String,
+    creator_id: String,
+    creator_name: String,
+    video_id: String,
+    game_id: String,
+    language: String,
+    title: String,
+    view_count: u64,
+    created_at: DateTime<Utc>,
+    thumbnail_url: String,
+    #[serde(deserialize_with = "deserialize_seconds")]
+    duration: Duration,
+    #[serde(default)]
+    vod_offset: Option<u64>,
+    #[serde(default)]
+    is_featured: bool,
+}
+
+fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Duration, D::Error> {
+    let secs = f64::deserialize(deserializer)?;
+    Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+}
+
+impl Clip {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub fn embed_url(&self) -> &str {
+        self.embed_url.as_str()
+    }
+
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn creator_id(&self) -> &str {
+        self.creator_id.as_str()
+    }
+
+    pub fn creator_name(&self) -> &str {
+        self.creator_name.as_str()
+    }
+
+    // None if the VOD is gone or was never saved
+    pub fn video_id(&self) -> Option<&str> {
+        match self.video_id.is_empty() {
+            true => None,
+            false => Some(self.video_id.as_str())
+        }
+    }
+
+    pub fn game_id(&self) -> &str {
+        self.game_id.as_str()
+    }
+
+    pub fn language(&self) -> &str {
+        self.language.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn view_count(&self) -> u64 {
+        self.view_count
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn thumbnail_url(&self) -> &str {
+        self.thumbnail_url.as_str()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    // where in the VOD the clip starts, in seconds
+    pub fn vod_offset(&self) -> Option<u64> {
+        self.vod_offset
+    }
+
+    pub fn is_featured(&self) -> bool {
+        self.is_featured
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ClipSource {
+    Broadcaster(String),
+    Game(String),
+    Ids(Vec<String>),
+}
+
+#[derive(Clone, Debug)]
+pub struct ClipFilter {
+    source: ClipSource,
+    started_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
+    is_featured: Option<bool>,
+}
+
+impl ClipFilter {
+    fn new(source: ClipSource) -> Self {
+        Self {
+            source,
+            started_at: None,
+            ended_at: None,
+            is_featured: None,
+        }
+    }
+
+    pub fn for_broadcaster(broadcaster_id: impl Into<String>) -> Self {
+        Self::new(ClipSource::Broadcaster(broadcaster_id.into()))
+    }
+
+    pub fn for_game(game_id: impl Into<String>) -> Self {
+        Self::new(ClipSource::Game(game_id.into()))
+    }
+
+    // up to 100 ids
+    pub fn for_ids(ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::new(ClipSource::Ids(ids.into_iter().map(Into::into).collect()))
+    }
+
+    // without an end date, Twitch looks at the week after the start date
+    pub fn with_started_at(mut self, started_at: DateTime<Utc>) -> Self {
+        self.started_at = Some(started_at);
+        self
+    }
+
+    pub fn with_ended_at(mut self, ended_at: DateTime<Utc>) -> Self {
+        self.ended_at = Some(ended_at);
+        self
+    }
+
+    pub fn with_featured(mut self, is_featured: bool) -> Self {
+        self.is_featured = Some(is_featured);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ClipTimeoutError {
+    clip_id: String,
+    timeout: Duration,
+}
+
+impl ClipTimeoutError {
+    pub fn new(clip_id: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            clip_id: clip_id.into(),
+            timeout,
+        }
+    }
+
+    pub fn clip_id(&self) -> &str {
+        self.clip_id.as_str()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Error for ClipTimeoutError {}
+
+impl Display for ClipTimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "clip {} was not ready after {} seconds", self.clip_id, self.timeout.as_secs())
+    }
+}
+
+impl ApiClient {
+    // the clip is only created in the background; see create_clip_and_wait
+    pub async fn create_clip(&self, broadcaster_id: impl ToString, has_delay: bool) -> Result<CreatedClip> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("clips")
+            .with_method(Method::POST)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("has_delay", has_delay.to_string())
+            .with_scope(Scope::ClipsEdit)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<CreatedClip> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    pub async fn create_clip_and_wait(&self, broadcaster_id: impl ToString, has_delay: bool, timeout: Duration) -> Result<Clip> {
+        let created = self.create_clip(broadcaster_id, has_delay).await?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            tokio::time::sleep(CLIP_POLL_INTERVAL).await;
+            if let Some(clip) = self.get_clip(created.id()).await? {
+                return Ok(clip);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Box::new(ClipTimeoutError::new(created.id(), timeout)));
+            }
+        }
+    }
+
+    pub async fn get_clip(&self, id: impl ToString) -> Result<Option<Clip>> {
+        let page = self.get_clips(&ClipFilter::for_ids(vec![id.to_string()]), &PageRequest::new()).await?;
+        Ok(page.into_data().into_iter().next())
+    }
+
+    pub async fn get_clips(&self, filter: &ClipFilter, page: &PageRequest) -> Result<Page<Clip>> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("clips");
+        builder = match &filter.source {
+            ClipSource::Broadcaster(broadcaster_id) => builder.with_param("broadcaster_id", broadcaster_id.as_str()),
+            ClipSource::Game(game_id) => builder.with_param("game_id", game_id.as_str()),
+            ClipSource::Ids(ids) => ids.iter().fold(builder, |builder, id| builder.with_param("id", id.as_str())),
+        };
+        if let Some(started_at) = filter.started_at {
+            builder = builder.with_param("started_at", started_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if let Some(ended_at) = filter.ended_at {
+            builder = builder.with_param("ended_at", ended_at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        if let Some(is_featured) = filter.is_featured {
+            builder = builder.with_param("is_featured", is_featured.to_string());
+        }
+        let response: HelixPaginatedResponse<Clip> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{deserialize_seconds, Clip};
+
+    #[test]
+    fn parses_fractional_durations() {
+        let clip: Clip = serde_json::from_str(r#"{
+            "id": "AwkwardHelplessSalamanderSwiftRage",
+            "url": "https://clips.twitch.tv/AwkwardHelplessSalamanderSwiftRage",
+            "embed_url": "https://clips.twitch.tv/embed?clip=AwkwardHelplessSalamanderSwiftRage",
+            "broadcaster_id": "67955580",
+            "broadcaster_name": "ChewieMelodies",
+            "creator_id": "53834192",
+            "creator_name": "BlackNova03",
+            "video_id": "",
+            "game_id": "488191",
+            "language": "en",
+            "title": "babymetal",
+            "view_count": 10,
+            "created_at": "2017-11-30T22:34:18Z",
+            "thumbnail_url": "https://clips-media-assets.twitch.tv/157589949-preview-480x272.jpg",
+            "duration": 28.3,
+            "vod_offset": null,
+            "is_featured": false
+        }"#).unwrap();
+        assert_eq!(clip.duration(), Duration::from_millis(28300));
+        assert_eq!(clip.video_id(), None);
+        assert_eq!(clip.vod_offset(), None);
+        assert!(deserialize_seconds(serde_json::json!(-1.0)).is_err());
+        assert!(deserialize_seconds(serde_json::json!(1e300)).is_err());
+    }
+}
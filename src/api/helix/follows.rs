@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChannelFollower {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    followed_at: DateTime<Utc>,
+}
+
+impl ChannelFollower {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn followed_at(&self) -> DateTime<Utc> {
+        self.followed_at
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FollowedChannel {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    followed_at: DateTime<Utc>,
+}
+
+impl FollowedChannel {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn followed_at(&self) -> DateTime<Utc> {
+        self.followed_at
+    }
+}
+
+impl ApiClient {
+    // needs a token of the broadcaster or one of their moderators
+    pub async fn get_channel_followers(&self, broadcaster_id: impl ToString, page: &PageRequest) -> Result<Page<ChannelFollower>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/followers")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_scope(Scope::ModeratorReadFollowers)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<ChannelFollower> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // any user token can see the total, even without being a moderator of the channel
+    pub async fn get_channel_follower_count(&self, broadcaster_id: impl ToString) -> Result<u64> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/followers")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("first", "1")
+            .with_token_type(TokenType::User)
+            .build()?;
+        let response: HelixPaginatedResponse<ChannelFollower> = self.call_api(call).await?;
+        Ok(Page::new(response, &PageRequest::new()).total().unwrap_or_default())
+    }
+
+    // None if the user does not follow the channel
+    pub async fn get_channel_follower(&self, broadcaster_id: impl ToString, user_id: impl ToString) -> Result<Option<ChannelFollower>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/followers")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::ModeratorReadFollowers)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let response: HelixPaginatedResponse<ChannelFollower> = self.call_api(call).await?;
+        Ok(Page::new(response, &PageRequest::new()).into_data().into_iter().next())
+    }
+
+    pub async fn get_followed_channels(&self, user_id: impl ToString, page: &PageRequest) -> Result<Page<FollowedChannel>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/followed")
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::UserReadFollows)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<FollowedChannel> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // the other direction of get_channel_follower, checked with the user's own token
+    pub async fn get_followed_channel(&self, user_id: impl ToString, broadcaster_id: impl ToString) -> Result<Option<FollowedChannel>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/followed")
+            .with_param("user_id", user_id.to_string())
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_scope(Scope::UserReadFollows)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let response: HelixPaginatedResponse<FollowedChannel> = self.call_api(call).await?;
+        Ok(Page::new(response, &PageRequest::new()).into_data().into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::helix::{HelixPaginatedResponse, Page, PageRequest};
+    use super::ChannelFollower;
+
+    #[test]
+    fn keeps_total_without_data() {
+        let response: HelixPaginatedResponse<ChannelFollower> = serde_json::from_str(r#"{
+            "total": 8,
+            "data": [],
+            "pagination": {}
+        }"#).unwrap();
+        let page = Page::new(response, &PageRequest::new());
+        assert_eq!(page.total(), Some(8));
+        assert!(page.data().is_empty());
+        assert!(page.next_page().is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{template_image_url, CacheEntry, HelixPaginatedResponse, Page, PageRequest};
+use crate::util::Result;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Game {
+    id: String,
+    name: String,
+    #[serde(deserialize_with = "deserialize_box_art_url")]
+    box_art_url: String,
+    // not included in search results
+    #[serde(default)]
+    igdb_id: String,
+}
+
+// search results come with a fixed size (-52x72.jpg) instead of the {width}x{height} template
+fn deserialize_box_art_url<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<String, D::Error> {
+    Ok(template_fixed_size(String::deserialize(deserializer)?))
+}
+
+fn template_fixed_size(url: String) -> String {
+    let (size_start, size_end) = match (url.rfind('-'), url.rfind('.')) {
+        (Some(dash), Some(dot)) if dash < dot => (dash + 1, dot),
+        _ => return url
+    };
+    let is_size = match url[size_start..size_end].split_once('x') {
+        Some((width, height)) => [width, height].iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit())),
+        None => false
+    };
+    match is_size {
+        true => format!("{}{{width}}x{{height}}{}", &url[..size_start], &url[size_end..]),
+        false => url
+    }
+}
+
+impl Game {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn box_art_url_template(&self) -> &str {
+        self.box_art_url.as_str()
+    }
+
+    pub fn box_art_url(&self, width: u32, height: u32) -> String {
+        template_image_url(self.box_art_url.as_str(), width, height)
+    }
+
+    pub fn igdb_id(&self) -> Option<&str> {
+        match self.igdb_id.is_empty() {
+            true => None,
+            false => Some(self.igdb_id.as_str())
+        }
+    }
+}
+
+impl CacheEntry for Game {
+    fn cache_keys(&self) -> Vec<(&'static str, String)> {
+        let mut keys = vec![("id", self.id.clone()), ("name", self.name.clone())];
+        if !self.igdb_id.is_empty() {
+            keys.push(("igdb_id", self.igdb_id.clone()));
+        }
+        keys
+    }
+}
+
+impl ApiClient {
+    pub async fn get_game_by_id(&self, id: impl ToString) -> Result<Option<Game>> {
+        Ok(self.get_games_by_ids(vec![id.to_string()]).await?.into_iter().next())
+    }
+
+    // the name has to match exactly
+    pub async fn get_game_by_name(&self, name: impl ToString) -> Result<Option<Game>> {
+        Ok(self.get_games_by_names(vec![name.to_string()]).await?.into_iter().next())
+    }
+
+    pub async fn get_games_by_ids(&self, ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<Game>> {
+        self.get_games(ids.into_iter().map(|id| ("id", id.to_string())).collect()).await
+    }
+
+    pub async fn get_games_by_names(&self, names: impl IntoIterator<Item = impl ToString>) -> Result<Vec<Game>> {
+        self.get_games(names.into_iter().map(|name| ("name", name.to_string())).collect()).await
+    }
+
+    pub async fn get_games_by_igdb_ids(&self, igdb_ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<Game>> {
+        self.get_games(igdb_ids.into_iter().map(|igdb_id| ("igdb_id", igdb_id.to_string())).collect()).await
+    }
+
+    async fn get_games(&self, params: Vec<(&'static str, String)>) -> Result<Vec<Game>> {
+        self.batched_lookup("games", &self.cache().games, params).await
+    }
+
+    pub async fn get_top_games(&self, page: &PageRequest) -> Result<Page<Game>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("games/top");
+        let response: HelixPaginatedResponse<Game> = self.call_api(page.apply(builder).build()?).await?;
+        let page = Page::new(response, page);
+        for game in page.data() {
+            self.cache().games.insert(game);
+        }
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Game;
+
+    #[test]
+    fn templates_box_art() {
+        let game: Game = serde_json::from_str(r#"{
+            "id": "33214",
+            "name": "Fortnite",
+            "box_art_url": "https://static-cdn.jtvnw.net/ttv-boxart/33214-{width}x{height}.jpg",
+            "igdb_id": "1905"
+        }"#).unwrap();
+        assert_eq!(game.box_art_url(52, 72), "https://static-cdn.jtvnw.net/ttv-boxart/33214-52x72.jpg");
+        assert_eq!(game.igdb_id(), Some("1905"));
+    }
+
+    #[test]
+    fn templates_fixed_size_box_art_of_search_results() {
+        let game: Game = serde_json::from_str(r#"{
+            "box_art_url": "https://static-cdn.jtvnw.net/ttv-boxart/33214-52x72.jpg",
+            "name": "Fortnite",
+            "id": "33214"
+        }"#).unwrap();
+        assert_eq!(game.box_art_url_template(), "https://static-cdn.jtvnw.net/ttv-boxart/33214-{width}x{height}.jpg");
+        assert_eq!(game.box_art_url(285, 380), "https://static-cdn.jtvnw.net/ttv-boxart/33214-285x380.jpg");
+        assert_eq!(game.igdb_id(), None);
+    }
+}
@@ -0,0 +1,210 @@
+mod automod;
+mod bits;
+mod cache;
+mod channel_points;
+mod channels;
+mod chat;
+mod clips;
+mod follows;
+mod games;
+mod moderation;
+mod polls;
+mod predictions;
+mod search;
+mod streams;
+mod subscriptions;
+mod users;
+mod videos;
+
+use std::collections::HashSet;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchAPICallBuilder, TwitchApiCallType};
+use crate::util::Result;
+
+pub(crate) use self::cache::{CacheEntry, HelixCache, LookupCache, DEFAULT_CACHE_TTL};
+
+pub use self::automod::{AutoModSettings, AutoModStatus, BlockedTerm};
+pub use self::bits::{BitsLeaderboard, BitsLeaderboardEntry, BitsLeaderboardPeriod, BitsLeaderboardQuery, Cheermote, CheermoteFormat, CheermoteScale, CheermoteTheme, CheermoteTier, Cheermotes, ResolvedCheer};
+pub use self::channel_points::{CustomReward, CustomRewardImage, CustomRewardRedemption, CustomRewardSettings, RedemptionFilter, RedemptionReward, RedemptionSort, RedemptionStatus, RewardNotManageableError};
+pub use self::channels::{ChannelEditor, ChannelInformation, ChannelUpdate, ContentClassificationLabel};
+pub use self::chat::{AnnouncementColor, ChatBadge, ChatBadgeSet, ChatSettings, ChatSettingsUpdate, Chatter, Emote, EmoteFormat, EmoteScale, EmoteTheme, UserChatColor};
+pub use self::clips::{Clip, ClipFilter, ClipTimeoutError, CreatedClip, DEFAULT_CLIP_TIMEOUT};
+pub use self::follows::{ChannelFollower, FollowedChannel};
+pub use self::games::Game;
+pub use self::moderation::{BannedUser, ChannelUser, ModeratedChannel, UnbanRequest, UnbanRequestStatus, UserBan};
+pub use self::polls::{Poll, PollChoice, PollRequest, PollStatus};
+pub use self::predictions::{OutcomeColor, Prediction, PredictionOutcome, PredictionStatus, Predictor};
+pub use self::search::ChannelSearchResult;
+pub use self::streams::{Stream, StreamFilter, StreamMarker, StreamType, UserStreamMarkers, VideoStreamMarkers};
+pub use self::subscriptions::{Gifter, SubTier, Subscription, UserSubscription};
+pub use self::users::{BroadcasterType, User, UserType};
+pub use self::videos::{MutedSegment, Video, VideoFilter, VideoPeriod, VideoSort, VideoType};
+
+// every Helix endpoint wraps its results in a `data` array
+#[derive(Deserialize, Debug)]
+pub(crate) struct HelixResponse<T> {
+    pub(crate) data: Vec<T>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct HelixPagination {
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HelixPaginatedResponse<T> {
+    data: Vec<T>,
+    #[serde(default)]
+    pagination: HelixPagination,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    points: Option<u64>,
+}
+
+// Helix accepts at most this many ids / logins in a single request
+pub(crate) const MAX_BATCH_SIZE: usize = 100;
+
+#[derive(Clone, Debug, Default)]
+pub struct PageRequest {
+    first: Option<u32>,
+    after: Option<String>,
+}
+
+impl PageRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // most endpoints allow up to 100 results per page and default to 20
+    pub fn with_first(mut self, first: u32) -> Self {
+        self.first = Some(first);
+        self
+    }
+
+    pub fn with_after(mut self, cursor: impl Into<String>) -> Self {
+        self.after = Some(cursor.into());
+        self
+    }
+
+    pub(crate) fn apply<'a, T>(&self, mut builder: TwitchAPICallBuilder<'a, T>) -> TwitchAPICallBuilder<'a, T> {
+        if let Some(first) = self.first {
+            builder = builder.with_param("first", first.to_string());
+        }
+        if let Some(after) = &self.after {
+            builder = builder.with_param("after", after.clone());
+        }
+        builder
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    data: Vec<T>,
+    cursor: Option<String>,
+    total: Option<u64>,
+    points: Option<u64>,
+    first: Option<u32>,
+}
+
+impl<T> Page<T> {
+    pub(crate) fn new(response: HelixPaginatedResponse<T>, request: &PageRequest) -> Self {
+        Self {
+            data: response.data,
+            // Twitch sometimes sends an empty cursor on the last page
+            cursor: response.pagination.cursor.filter(|cursor| !cursor.is_empty()),
+            total: response.total,
+            points: response.points,
+            first: request.first,
+        }
+    }
+
+    pub fn data(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    pub fn into_data(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    // only reported by some endpoints
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    // sub points, only reported by the broadcaster subscriptions endpoint
+    pub fn points(&self) -> Option<u64> {
+        self.points
+    }
+
+    // the request for the page after this one, with the same page size; None on the last page
+    pub fn next_page(&self) -> Option<PageRequest> {
+        self.cursor.as_ref().map(|cursor| PageRequest {
+            first: self.first,
+            after: Some(cursor.clone()),
+        })
+    }
+}
+
+impl ApiClient {
+    // serves what it can from the cache and fetches the rest in batches; unknown ids etc. are left out
+    pub(crate) async fn batched_lookup<T>(
+        &self,
+        url: &'static str,
+        cache: &LookupCache<T>,
+        params: Vec<(&'static str, String)>,
+    ) -> Result<Vec<T>>
+        where T: CacheEntry + serde::de::DeserializeOwned {
+        let mut results = Vec::with_capacity(params.len());
+        let mut missing = Vec::new();
+        for (key, value) in params {
+            match cache.get(key, value.as_str()) {
+                Some(entry) => results.push(entry),
+                None if !missing.contains(&(key, value.clone())) => missing.push((key, value)),
+                None => {}
+            }
+        }
+
+        for chunk in missing.chunks(MAX_BATCH_SIZE) {
+            let mut builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url(url);
+            for (key, value) in chunk {
+                builder = builder.with_param(*key, value.as_str());
+            }
+            let response: HelixResponse<T> = self.call_api(builder.build()?).await?;
+            for entry in response.data {
+                cache.insert(&entry);
+                results.push(entry);
+            }
+        }
+
+        // the same entry might have been asked for by e.g. both id and login; the first key identifies it
+        let mut seen = HashSet::new();
+        results.retain(|entry| seen.insert(entry.cache_keys().swap_remove(0)));
+        Ok(results)
+    }
+}
+
+// Twitch sends an empty string instead of null for dates that are not set
+pub(crate) fn deserialize_optional_date<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<DateTime<Utc>>, D::Error> {
+    let date = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    match date.is_empty() {
+        true => Ok(None),
+        false => date.parse().map(Some).map_err(serde::de::Error::custom)
+    }
+}
+
+// thumbnails, box art etc. come as templates with {width} and {height} placeholders; videos use %{width} and %{height}
+pub(crate) fn template_image_url(url: &str, width: u32, height: u32) -> String {
+    url.replace("%{width}", "{width}")
+        .replace("%{height}", "{height}")
+        .replace("{width}", width.to_string().as_str())
+        .replace("{height}", height.to_string().as_str())
+}
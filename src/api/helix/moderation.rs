@@ -0,0 +1,507 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType, UnknownValueError};
+use crate::api::helix::{deserialize_optional_date, HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserBan {
+    broadcaster_id: String,
+    moderator_id: String,
+    user_id: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    end_time: Option<DateTime<Utc>>,
+}
+
+impl UserBan {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn moderator_id(&self) -> &str {
+        self.moderator_id.as_str()
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // None for permanent bans
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        self.end_time
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BannedUser {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    reason: String,
+    moderator_id: String,
+    moderator_login: String,
+    moderator_name: String,
+}
+
+impl BannedUser {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    // None for permanent bans
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.expires_at.is_some()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn reason(&self) -> &str {
+        self.reason.as_str()
+    }
+
+    pub fn moderator_id(&self) -> &str {
+        self.moderator_id.as_str()
+    }
+
+    pub fn moderator_login(&self) -> &str {
+        self.moderator_login.as_str()
+    }
+
+    pub fn moderator_name(&self) -> &str {
+        self.moderator_name.as_str()
+    }
+}
+
+// moderators and VIPs of a channel
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChannelUser {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+}
+
+impl ChannelUser {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ModeratedChannel {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+}
+
+impl ModeratedChannel {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnbanRequestStatus {
+    Pending,
+    Approved,
+    Denied,
+    Acknowledged,
+    Canceled,
+    // statuses Twitch added later; filtering by it fails with an UnknownValueError
+    #[serde(other)]
+    Unknown,
+}
+
+impl UnbanRequestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnbanRequestStatus::Pending => "pending",
+            UnbanRequestStatus::Approved => "approved",
+            UnbanRequestStatus::Denied => "denied",
+            UnbanRequestStatus::Acknowledged => "acknowledged",
+            UnbanRequestStatus::Canceled => "canceled",
+            UnbanRequestStatus::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UnbanRequest {
+    id: String,
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    #[serde(default)]
+    moderator_id: Option<String>,
+    #[serde(default)]
+    moderator_login: Option<String>,
+    #[serde(default)]
+    moderator_name: Option<String>,
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    text: String,
+    status: UnbanRequestStatus,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    resolved_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    resolution_text: Option<String>,
+}
+
+impl UnbanRequest {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    // the moderator who resolved the request
+    pub fn moderator_id(&self) -> Option<&str> {
+        self.moderator_id.as_deref().filter(|id| !id.is_empty())
+    }
+
+    pub fn moderator_login(&self) -> Option<&str> {
+        self.moderator_login.as_deref().filter(|login| !login.is_empty())
+    }
+
+    pub fn moderator_name(&self) -> Option<&str> {
+        self.moderator_name.as_deref().filter(|name| !name.is_empty())
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    pub fn status(&self) -> UnbanRequestStatus {
+        self.status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn resolved_at(&self) -> Option<DateTime<Utc>> {
+        self.resolved_at
+    }
+
+    pub fn resolution_text(&self) -> Option<&str> {
+        self.resolution_text.as_deref().filter(|text| !text.is_empty())
+    }
+}
+
+#[derive(Serialize)]
+struct BanData {
+    user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BanUserBody {
+    data: BanData,
+}
+
+impl ApiClient {
+    // moderator-scoped endpoints act as the token's own user unless a moderator id is given
+    pub(crate) async fn moderator_id(&self, moderator_id: Option<&str>) -> Result<String> {
+        match moderator_id {
+            Some(moderator_id) => Ok(moderator_id.to_string()),
+            None => self.token_user_id().await
+        }
+    }
+
+    pub async fn ban_user(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, user_id: impl ToString, reason: Option<&str>) -> Result<UserBan> {
+        self.ban(broadcaster_id.to_string(), moderator_id, user_id.to_string(), None, reason).await
+    }
+
+    // between 1 second and 2 weeks
+    pub async fn timeout_user(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, user_id: impl ToString, duration: Duration, reason: Option<&str>) -> Result<UserBan> {
+        self.ban(broadcaster_id.to_string(), moderator_id, user_id.to_string(), Some(duration), reason).await
+    }
+
+    async fn ban(&self, broadcaster_id: String, moderator_id: Option<&str>, user_id: String, duration: Option<Duration>, reason: Option<&str>) -> Result<UserBan> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::<BanUserBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/bans")
+            .with_method(Method::POST)
+            .with_param("broadcaster_id", broadcaster_id)
+            .with_param("moderator_id", moderator_id)
+            .with_body(BanUserBody {
+                data: BanData {
+                    user_id,
+                    duration: duration.map(|duration| duration.as_secs().max(1)),
+                    reason: reason.map(str::to_string),
+                },
+            })
+            .with_scope(Scope::ModeratorManageBannedUsers)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<UserBan> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    // lifts bans as well as timeouts
+    pub async fn unban_user(&self, broadcaster_id: impl ToString, moderator_id: Option<&str>, user_id: impl ToString) -> Result<()> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/bans")
+            .with_method(Method::DELETE)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::ModeratorManageBannedUsers)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    // all banned users if no user ids are given, otherwise up to 100 of them
+    pub async fn get_banned_users(&self, broadcaster_id: impl ToString, user_ids: impl IntoIterator<Item = impl ToString>, page: &PageRequest) -> Result<Page<BannedUser>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/banned")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_any_scope(vec![Scope::ModerationRead, Scope::ModeratorManageBannedUsers])
+            .with_token_type(TokenType::User);
+        let builder = user_ids.into_iter().fold(builder, |builder, user_id| builder.with_param("user_id", user_id.to_string()));
+        let response: HelixPaginatedResponse<BannedUser> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn get_moderators(&self, broadcaster_id: impl ToString, user_ids: impl IntoIterator<Item = impl ToString>, page: &PageRequest) -> Result<Page<ChannelUser>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/moderators")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_any_scope(vec![Scope::ModerationRead, Scope::ChannelManageModerators])
+            .with_token_type(TokenType::User);
+        let builder = user_ids.into_iter().fold(builder, |builder, user_id| builder.with_param("user_id", user_id.to_string()));
+        let response: HelixPaginatedResponse<ChannelUser> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn add_moderator(&self, broadcaster_id: impl ToString, user_id: impl ToString) -> Result<()> {
+        self.change_channel_role("moderation/moderators", Method::POST, Scope::ChannelManageModerators, broadcaster_id.to_string(), user_id.to_string()).await
+    }
+
+    pub async fn remove_moderator(&self, broadcaster_id: impl ToString, user_id: impl ToString) -> Result<()> {
+        self.change_channel_role("moderation/moderators", Method::DELETE, Scope::ChannelManageModerators, broadcaster_id.to_string(), user_id.to_string()).await
+    }
+
+    pub async fn get_vips(&self, broadcaster_id: impl ToString, user_ids: impl IntoIterator<Item = impl ToString>, page: &PageRequest) -> Result<Page<ChannelUser>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("channels/vips")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_any_scope(vec![Scope::ChannelReadVips, Scope::ChannelManageVips])
+            .with_token_type(TokenType::User);
+        let builder = user_ids.into_iter().fold(builder, |builder, user_id| builder.with_param("user_id", user_id.to_string()));
+        let response: HelixPaginatedResponse<ChannelUser> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn add_vip(&self, broadcaster_id: impl ToString, user_id: impl ToString) -> Result<()> {
+        self.change_channel_role("channels/vips", Method::POST, Scope::ChannelManageVips, broadcaster_id.to_string(), user_id.to_string()).await
+    }
+
+    pub async fn remove_vip(&self, broadcaster_id: impl ToString, user_id: impl ToString) -> Result<()> {
+        self.change_channel_role("channels/vips", Method::DELETE, Scope::ChannelManageVips, broadcaster_id.to_string(), user_id.to_string()).await
+    }
+
+    async fn change_channel_role(&self, url: &str, method: Method, scope: Scope, broadcaster_id: String, user_id: String) -> Result<()> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url(url)
+            .with_method(method)
+            .with_param("broadcaster_id", broadcaster_id)
+            .with_param("user_id", user_id)
+            .with_scope(scope)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.call_api(call).await
+    }
+
+    // the channels the user is a moderator in
+    pub async fn get_moderated_channels(&self, user_id: impl ToString, page: &PageRequest) -> Result<Page<ModeratedChannel>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/channels")
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::UserReadModeratedChannels)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<ModeratedChannel> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn get_unban_requests(
+        &self,
+        broadcaster_id: impl ToString,
+        moderator_id: Option<&str>,
+        status: UnbanRequestStatus,
+        user_id: Option<&str>,
+        page: &PageRequest,
+    ) -> Result<Page<UnbanRequest>> {
+        if status == UnbanRequestStatus::Unknown {
+            return Err(Box::new(UnknownValueError::new("status")));
+        }
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/unban_requests")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_param("status", status.as_str())
+            .with_any_scope(vec![Scope::ModeratorReadUnbanRequests, Scope::ModeratorManageUnbanRequests])
+            .with_token_type(TokenType::User);
+        if let Some(user_id) = user_id {
+            builder = builder.with_param("user_id", user_id);
+        }
+        let response: HelixPaginatedResponse<UnbanRequest> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // approving lifts the ban; the resolution text is shown to the user
+    pub async fn resolve_unban_request(
+        &self,
+        broadcaster_id: impl ToString,
+        moderator_id: Option<&str>,
+        unban_request_id: impl ToString,
+        approve: bool,
+        resolution_text: Option<&str>,
+    ) -> Result<UnbanRequest> {
+        let moderator_id = self.moderator_id(moderator_id).await?;
+        let status = match approve {
+            true => UnbanRequestStatus::Approved,
+            false => UnbanRequestStatus::Denied
+        };
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("moderation/unban_requests")
+            .with_method(Method::PATCH)
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("moderator_id", moderator_id)
+            .with_param("unban_request_id", unban_request_id.to_string())
+            .with_param("status", status.as_str())
+            .with_scope(Scope::ModeratorManageUnbanRequests)
+            .with_token_type(TokenType::User);
+        if let Some(resolution_text) = resolution_text {
+            builder = builder.with_param("resolution_text", resolution_text);
+        }
+        let call = builder.build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<UnbanRequest> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BannedUser, UnbanRequest, UnbanRequestStatus};
+
+    #[test]
+    fn parses_permanent_bans_and_pending_unban_requests() {
+        let banned: BannedUser = serde_json::from_str(r#"{
+            "user_id": "423374343",
+            "user_login": "glowillig",
+            "user_name": "glowillig",
+            "expires_at": "",
+            "created_at": "2022-03-15T02:00:28Z",
+            "reason": "Does not like pineapple on pizza.",
+            "moderator_id": "141981764",
+            "moderator_login": "twitchdev",
+            "moderator_name": "TwitchDev"
+        }"#).unwrap();
+        assert!(!banned.is_timeout());
+
+        let request: UnbanRequest = serde_json::from_str(r#"{
+            "id": "92af127c-7326-4483-a52b-b0da0be61c01",
+            "broadcaster_name": "torpedo09",
+            "broadcaster_login": "torpedo09",
+            "broadcaster_id": "274637212",
+            "moderator_id": "",
+            "moderator_login": "",
+            "moderator_name": "",
+            "user_id": "141981764",
+            "user_login": "twitchdev",
+            "user_name": "TwitchDev",
+            "text": "Please unban me from the channel?",
+            "status": "pending",
+            "created_at": "2022-08-07T02:07:55Z",
+            "resolved_at": null,
+            "resolution_text": null
+        }"#).unwrap();
+        assert_eq!(request.status(), UnbanRequestStatus::Pending);
+        assert_eq!(request.moderator_id(), None);
+    }
+}
@@ -0,0 +1,286 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PollStatus {
+    Active,
+    Completed,
+    Terminated,
+    Archived,
+    Moderated,
+    Invalid,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PollChoice {
+    id: String,
+    title: String,
+    votes: u64,
+    channel_points_votes: u64,
+}
+
+impl PollChoice {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn votes(&self) -> u64 {
+        self.votes
+    }
+
+    pub fn channel_points_votes(&self) -> u64 {
+        self.channel_points_votes
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Poll {
+    id: String,
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    title: String,
+    choices: Vec<PollChoice>,
+    channel_points_voting_enabled: bool,
+    channel_points_per_vote: u64,
+    status: PollStatus,
+    duration: u64,
+    started_at: DateTime<Utc>,
+    #[serde(default)]
+    ended_at: Option<DateTime<Utc>>,
+}
+
+impl Poll {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn choices(&self) -> &[PollChoice] {
+        self.choices.as_slice()
+    }
+
+    pub fn channel_points_per_vote(&self) -> Option<u64> {
+        match self.channel_points_voting_enabled {
+            true => Some(self.channel_points_per_vote),
+            false => None
+        }
+    }
+
+    pub fn status(&self) -> PollStatus {
+        self.status
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration)
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn ended_at(&self) -> Option<DateTime<Utc>> {
+        self.ended_at
+    }
+
+    // None while the poll is running or if nobody voted; ties go to the first choice
+    pub fn winning_choice(&self) -> Option<&PollChoice> {
+        self.choices.iter()
+            .filter(|choice| choice.votes > 0)
+            .fold(None, |winner: Option<&PollChoice>, choice| match winner {
+                Some(winner) if winner.votes >= choice.votes => Some(winner),
+                _ => Some(choice)
+            })
+            .filter(|_| self.status != PollStatus::Active)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct PollChoiceTitle {
+    title: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PollRequest {
+    title: String,
+    choices: Vec<PollChoiceTitle>,
+    duration: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_points_voting_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_points_per_vote: Option<u64>,
+}
+
+impl PollRequest {
+    // 2 to 5 choices, running between 15 seconds and 30 minutes
+    pub fn new(title: impl Into<String>, choices: impl IntoIterator<Item = impl Into<String>>, duration: Duration) -> Self {
+        Self {
+            title: title.into(),
+            choices: choices.into_iter().map(|title| PollChoiceTitle { title: title.into() }).collect(),
+            duration: duration.as_secs(),
+            channel_points_voting_enabled: None,
+            channel_points_per_vote: None,
+        }
+    }
+
+    // lets viewers buy additional votes
+    pub fn with_channel_points_per_vote(mut self, channel_points_per_vote: u64) -> Self {
+        self.channel_points_voting_enabled = Some(true);
+        self.channel_points_per_vote = Some(channel_points_per_vote);
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct CreatePollBody<'a> {
+    broadcaster_id: String,
+    #[serde(flatten)]
+    request: &'a PollRequest,
+}
+
+#[derive(Serialize)]
+struct EndPollBody {
+    broadcaster_id: String,
+    id: String,
+    status: &'static str,
+}
+
+impl ApiClient {
+    // the most recent polls first if no ids are given
+    pub async fn get_polls(&self, broadcaster_id: impl ToString, ids: impl IntoIterator<Item = impl ToString>, page: &PageRequest) -> Result<Page<Poll>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("polls")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_any_scope(vec![Scope::ChannelReadPolls, Scope::ChannelManagePolls])
+            .with_token_type(TokenType::User);
+        let builder = ids.into_iter().fold(builder, |builder, id| builder.with_param("id", id.to_string()));
+        let response: HelixPaginatedResponse<Poll> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn create_poll(&self, broadcaster_id: impl ToString, request: &PollRequest) -> Result<Poll> {
+        let call = TwitchApiCall::<CreatePollBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("polls")
+            .with_method(Method::POST)
+            .with_body(CreatePollBody {
+                broadcaster_id: broadcaster_id.to_string(),
+                request,
+            })
+            .with_scope(Scope::ChannelManagePolls)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.poll_from_response(call).await
+    }
+
+    // ends the poll early and keeps showing the results
+    pub async fn end_poll(&self, broadcaster_id: impl ToString, poll_id: impl ToString) -> Result<Poll> {
+        self.update_poll_status(broadcaster_id.to_string(), poll_id.to_string(), "TERMINATED").await
+    }
+
+    // ends the poll early and hides it from the channel
+    pub async fn archive_poll(&self, broadcaster_id: impl ToString, poll_id: impl ToString) -> Result<Poll> {
+        self.update_poll_status(broadcaster_id.to_string(), poll_id.to_string(), "ARCHIVED").await
+    }
+
+    async fn update_poll_status(&self, broadcaster_id: String, poll_id: String, status: &'static str) -> Result<Poll> {
+        let call = TwitchApiCall::<EndPollBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("polls")
+            .with_method(Method::PATCH)
+            .with_body(EndPollBody {
+                broadcaster_id,
+                id: poll_id,
+                status,
+            })
+            .with_scope(Scope::ChannelManagePolls)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.poll_from_response(call).await
+    }
+
+    async fn poll_from_response<B: serde::Serialize>(&self, call: TwitchApiCall<'_, B>) -> Result<Poll> {
+        let url = call.full_url().to_string();
+        let response: HelixResponse<Poll> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::{CreatePollBody, Poll, PollRequest, PollStatus};
+
+    fn poll(status: &str, votes: &[u64]) -> Poll {
+        let choices: Vec<String> = votes.iter().enumerate()
+            .map(|(i, votes)| format!(r#"{{"id": "{}", "title": "choice {}", "votes": {}, "channel_points_votes": 0}}"#, i, i, votes))
+            .collect();
+        serde_json::from_str(format!(r#"{{
+            "id": "ed961efd-8a3f-4cf5-a9d0-e616c590cd2a",
+            "broadcaster_id": "55696719",
+            "broadcaster_name": "TwitchDev",
+            "broadcaster_login": "twitchdev",
+            "title": "Heads or Tails?",
+            "choices": [{}],
+            "channel_points_voting_enabled": false,
+            "channel_points_per_vote": 0,
+            "status": "{}",
+            "duration": 1800,
+            "started_at": "2021-03-19T06:08:33.871278372Z",
+            "ended_at": null
+        }}"#, choices.join(","), status).as_str()).unwrap()
+    }
+
+    #[test]
+    fn picks_the_winning_choice_of_ended_polls() {
+        assert_eq!(poll("COMPLETED", &[3, 5, 1]).winning_choice().unwrap().id(), "1");
+        assert_eq!(poll("TERMINATED", &[4, 4]).winning_choice().unwrap().id(), "0");
+        assert!(poll("COMPLETED", &[0, 0]).winning_choice().is_none());
+        assert!(poll("ACTIVE", &[3, 5]).winning_choice().is_none());
+        assert_eq!(poll("SOMETHING_NEW", &[1]).status(), PollStatus::Unknown);
+    }
+
+    #[test]
+    fn flattens_the_request_into_the_body() {
+        let request = PollRequest::new("Heads or Tails?", vec!["Heads", "Tails"], Duration::from_secs(60))
+            .with_channel_points_per_vote(100);
+        let body = CreatePollBody {
+            broadcaster_id: "55696719".to_string(),
+            request: &request,
+        };
+        assert_eq!(
+            serde_json::to_string(&body).unwrap(),
+            r#"{"broadcaster_id":"55696719","title":"Heads or Tails?","choices":[{"title":"Heads"},{"title":"Tails"}],"duration":60,"channel_points_voting_enabled":true,"channel_points_per_vote":100}"#
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PredictionStatus {
+    Active,
+    Locked,
+    Resolved,
+    Canceled,
+    // only ever received, never sent
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OutcomeColor {
+    Blue,
+    Pink,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Predictor {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    channel_points_used: u64,
+    #[serde(default)]
+    channel_points_won: Option<u64>,
+}
+
+impl Predictor {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn channel_points_used(&self) -> u64 {
+        self.channel_points_used
+    }
+
+    // only set once the prediction is resolved; 0 for predictors of losing outcomes
+    pub fn channel_points_won(&self) -> Option<u64> {
+        self.channel_points_won
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PredictionOutcome {
+    id: String,
+    title: String,
+    users: u64,
+    channel_points: u64,
+    #[serde(default)]
+    top_predictors: Option<Vec<Predictor>>,
+    color: OutcomeColor,
+}
+
+impl PredictionOutcome {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn users(&self) -> u64 {
+        self.users
+    }
+
+    pub fn channel_points(&self) -> u64 {
+        self.channel_points
+    }
+
+    // up to 10 users who spent the most points on this outcome
+    pub fn top_predictors(&self) -> &[Predictor] {
+        self.top_predictors.as_deref().unwrap_or(&[])
+    }
+
+    pub fn color(&self) -> OutcomeColor {
+        self.color
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Prediction {
+    id: String,
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    title: String,
+    #[serde(default)]
+    winning_outcome_id: Option<String>,
+    outcomes: Vec<PredictionOutcome>,
+    prediction_window: u64,
+    status: PredictionStatus,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    locked_at: Option<DateTime<Utc>>,
+}
+
+impl Prediction {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn outcomes(&self) -> &[PredictionOutcome] {
+        self.outcomes.as_slice()
+    }
+
+    pub fn winning_outcome(&self) -> Option<&PredictionOutcome> {
+        let winning_outcome_id = self.winning_outcome_id.as_deref()?;
+        self.outcomes.iter().find(|outcome| outcome.id == winning_outcome_id)
+    }
+
+    pub fn prediction_window(&self) -> Duration {
+        Duration::from_secs(self.prediction_window)
+    }
+
+    pub fn status(&self) -> PredictionStatus {
+        self.status
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn ended_at(&self) -> Option<DateTime<Utc>> {
+        self.ended_at
+    }
+
+    pub fn locked_at(&self) -> Option<DateTime<Utc>> {
+        self.locked_at
+    }
+}
+
+#[derive(Serialize)]
+struct OutcomeTitle {
+    title: String,
+}
+
+#[derive(Serialize)]
+struct CreatePredictionBody {
+    broadcaster_id: String,
+    title: String,
+    outcomes: Vec<OutcomeTitle>,
+    prediction_window: u64,
+}
+
+#[derive(Serialize)]
+struct EndPredictionBody {
+    broadcaster_id: String,
+    id: String,
+    status: PredictionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    winning_outcome_id: Option<String>,
+}
+
+impl ApiClient {
+    // the most recent predictions first if no ids are given
+    pub async fn get_predictions(&self, broadcaster_id: impl ToString, ids: impl IntoIterator<Item = impl ToString>, page: &PageRequest) -> Result<Page<Prediction>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("predictions")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_any_scope(vec![Scope::ChannelReadPredictions, Scope::ChannelManagePredictions])
+            .with_token_type(TokenType::User);
+        let builder = ids.into_iter().fold(builder, |builder, id| builder.with_param("id", id.to_string()));
+        let response: HelixPaginatedResponse<Prediction> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // 2 to 10 outcomes, taking predictions for 30 seconds up to 30 minutes
+    pub async fn create_prediction(
+        &self,
+        broadcaster_id: impl ToString,
+        title: impl Into<String>,
+        outcomes: impl IntoIterator<Item = impl Into<String>>,
+        prediction_window: Duration,
+    ) -> Result<Prediction> {
+        let call = TwitchApiCall::<CreatePredictionBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("predictions")
+            .with_method(Method::POST)
+            .with_body(CreatePredictionBody {
+                broadcaster_id: broadcaster_id.to_string(),
+                title: title.into(),
+                outcomes: outcomes.into_iter().map(|title| OutcomeTitle { title: title.into() }).collect(),
+                prediction_window: prediction_window.as_secs(),
+            })
+            .with_scope(Scope::ChannelManagePredictions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.prediction_from_response(call).await
+    }
+
+    // stops taking predictions before the window ends
+    pub async fn lock_prediction(&self, broadcaster_id: impl ToString, prediction_id: impl ToString) -> Result<Prediction> {
+        self.end_prediction(broadcaster_id.to_string(), prediction_id.to_string(), PredictionStatus::Locked, None).await
+    }
+
+    // pays out the points to everyone who picked the winning outcome
+    pub async fn resolve_prediction(&self, broadcaster_id: impl ToString, prediction_id: impl ToString, winning_outcome_id: impl ToString) -> Result<Prediction> {
+        self.end_prediction(broadcaster_id.to_string(), prediction_id.to_string(), PredictionStatus::Resolved, Some(winning_outcome_id.to_string())).await
+    }
+
+    // refunds all points that were spent on the prediction
+    pub async fn cancel_prediction(&self, broadcaster_id: impl ToString, prediction_id: impl ToString) -> Result<Prediction> {
+        self.end_prediction(broadcaster_id.to_string(), prediction_id.to_string(), PredictionStatus::Canceled, None).await
+    }
+
+    async fn end_prediction(&self, broadcaster_id: String, prediction_id: String, status: PredictionStatus, winning_outcome_id: Option<String>) -> Result<Prediction> {
+        let call = TwitchApiCall::<EndPredictionBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("predictions")
+            .with_method(Method::PATCH)
+            .with_body(EndPredictionBody {
+                broadcaster_id,
+                id: prediction_id,
+                status,
+                winning_outcome_id,
+            })
+            .with_scope(Scope::ChannelManagePredictions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        self.prediction_from_response(call).await
+    }
+
+    async fn prediction_from_response<B: serde::Serialize>(&self, call: TwitchApiCall<'_, B>) -> Result<Prediction> {
+        let url = call.full_url().to_string();
+        let response: HelixResponse<Prediction> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutcomeColor, Prediction, PredictionStatus};
+
+    #[test]
+    fn parses_resolved_predictions_with_top_predictors() {
+        let prediction: Prediction = serde_json::from_str(r#"{
+            "id": "d6676d5c-c86e-44d2-bfc4-100fb48f0656",
+            "broadcaster_id": "55696719",
+            "broadcaster_name": "TwitchDev",
+            "broadcaster_login": "twitchdev",
+            "title": "Will there be any leaks today?",
+            "winning_outcome_id": "021e9234-5893-49b4-982e-cfe9a0aaddd9",
+            "outcomes": [
+                {
+                    "id": "021e9234-5893-49b4-982e-cfe9a0aaddd9",
+                    "title": "Yes",
+                    "users": 1,
+                    "channel_points": 500,
+                    "top_predictors": [{"user_id": "1", "user_login": "a", "user_name": "A", "channel_points_used": 500, "channel_points_won": 1000}],
+                    "color": "BLUE"
+                },
+                {
+                    "id": "ded84c26-13cb-4b48-8cb5-5bae3ec3a66e",
+                    "title": "No",
+                    "users": 0,
+                    "channel_points": 0,
+                    "top_predictors": null,
+                    "color": "PINK"
+                }
+            ],
+            "prediction_window": 600,
+            "status": "RESOLVED",
+            "created_at": "2021-04-28T16:03:06.320848689Z",
+            "ended_at": "2021-04-28T16:08:06.320848689Z",
+            "locked_at": null
+        }"#).unwrap();
+        assert_eq!(prediction.status(), PredictionStatus::Resolved);
+        let winner = prediction.winning_outcome().unwrap();
+        assert_eq!(winner.title(), "Yes");
+        assert_eq!(winner.color(), OutcomeColor::Blue);
+        assert_eq!(winner.top_predictors()[0].channel_points_won(), Some(1000));
+        assert!(prediction.outcomes()[1].top_predictors().is_empty());
+        assert_eq!(prediction.prediction_window().as_secs(), 600);
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{deserialize_optional_date, Game, HelixPaginatedResponse, Page, PageRequest};
+use crate::util::Result;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChannelSearchResult {
+    id: String,
+    broadcaster_login: String,
+    display_name: String,
+    broadcaster_language: String,
+    game_id: String,
+    game_name: String,
+    is_live: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    thumbnail_url: String,
+    title: String,
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    started_at: Option<DateTime<Utc>>,
+}
+
+impl ChannelSearchResult {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_str()
+    }
+
+    pub fn broadcaster_language(&self) -> &str {
+        self.broadcaster_language.as_str()
+    }
+
+    pub fn game_id(&self) -> &str {
+        self.game_id.as_str()
+    }
+
+    pub fn game_name(&self) -> &str {
+        self.game_name.as_str()
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.is_live
+    }
+
+    pub fn tags(&self) -> &[String] {
+        self.tags.as_slice()
+    }
+
+    // the profile picture, not a stream preview
+    pub fn thumbnail_url(&self) -> &str {
+        self.thumbnail_url.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    // only set while the channel is live
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.started_at
+    }
+}
+
+impl ApiClient {
+    pub async fn search_categories(&self, query: impl ToString, page: &PageRequest) -> Result<Page<Game>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("search/categories")
+            .with_param("query", query.to_string());
+        let response: HelixPaginatedResponse<Game> = self.call_api(page.apply(builder).build()?).await?;
+        let page = Page::new(response, page);
+        // search results come without their IGDB id, so they don't replace complete cached games
+        for game in page.data() {
+            if self.cache().games.get("id", game.id()).is_none() {
+                self.cache().games.insert(game);
+            }
+        }
+        Ok(page)
+    }
+
+    pub async fn search_channels(&self, query: impl ToString, live_only: bool, page: &PageRequest) -> Result<Page<ChannelSearchResult>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("search/channels")
+            .with_param("query", query.to_string())
+            .with_param("live_only", live_only.to_string());
+        let response: HelixPaginatedResponse<ChannelSearchResult> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelSearchResult;
+
+    #[test]
+    fn parses_offline_channels_without_start_date() {
+        let result: ChannelSearchResult = serde_json::from_str(r#"{
+            "broadcaster_language": "en",
+            "broadcaster_login": "loserfruit",
+            "display_name": "Loserfruit",
+            "game_id": "498000",
+            "game_name": "House Flipper",
+            "id": "41245072",
+            "is_live": false,
+            "tags": [],
+            "thumbnail_url": "https://static-cdn.jtvnw.net/jtv_user_pictures/fd17325a-7dc2-46c6-8617-e90ec259501c-profile_image-300x300.png",
+            "title": "loserfruit",
+            "started_at": ""
+        }"#).unwrap();
+        assert!(!result.is_live());
+        assert_eq!(result.started_at(), None);
+    }
+}
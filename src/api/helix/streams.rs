@@ -0,0 +1,361 @@
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{template_image_url, HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, Secret, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamType {
+    All,
+    Live,
+}
+
+impl StreamType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamType::All => "all",
+            StreamType::Live => "live",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Stream {
+    id: String,
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    game_id: String,
+    game_name: String,
+    #[serde(rename = "type")]
+    stream_type: String,
+    title: String,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    viewer_count: u64,
+    started_at: DateTime<Utc>,
+    language: String,
+    thumbnail_url: String,
+    is_mature: bool,
+}
+
+impl Stream {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn game_id(&self) -> &str {
+        self.game_id.as_str()
+    }
+
+    pub fn game_name(&self) -> &str {
+        self.game_name.as_str()
+    }
+
+    // Twitch reports an empty type instead of "live" when something went wrong on their end
+    pub fn is_live(&self) -> bool {
+        self.stream_type == StreamType::Live.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        self.tags.as_deref().unwrap_or_default()
+    }
+
+    pub fn viewer_count(&self) -> u64 {
+        self.viewer_count
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    pub fn language(&self) -> &str {
+        self.language.as_str()
+    }
+
+    pub fn thumbnail_url_template(&self) -> &str {
+        self.thumbnail_url.as_str()
+    }
+
+    pub fn thumbnail_url(&self, width: u32, height: u32) -> String {
+        template_image_url(self.thumbnail_url.as_str(), width, height)
+    }
+
+    pub fn is_mature(&self) -> bool {
+        self.is_mature
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StreamFilter {
+    user_ids: Vec<String>,
+    user_logins: Vec<String>,
+    game_ids: Vec<String>,
+    languages: Vec<String>,
+    stream_type: Option<StreamType>,
+}
+
+impl StreamFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ids, logins and game ids can be repeated up to 100 times each
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_ids.push(user_id.into());
+        self
+    }
+
+    pub fn with_user_login(mut self, user_login: impl Into<String>) -> Self {
+        self.user_logins.push(user_login.into());
+        self
+    }
+
+    pub fn with_game_id(mut self, game_id: impl Into<String>) -> Self {
+        self.game_ids.push(game_id.into());
+        self
+    }
+
+    // ISO 639-1 codes, or "other"
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.languages.push(language.into());
+        self
+    }
+
+    pub fn with_type(mut self, stream_type: StreamType) -> Self {
+        self.stream_type = Some(stream_type);
+        self
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamMarker {
+    id: String,
+    created_at: DateTime<Utc>,
+    description: String,
+    position_seconds: u64,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl StreamMarker {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn position_seconds(&self) -> u64 {
+        self.position_seconds
+    }
+
+    // only included when listing markers, not when creating one
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct VideoStreamMarkers {
+    video_id: String,
+    markers: Vec<StreamMarker>,
+}
+
+impl VideoStreamMarkers {
+    pub fn video_id(&self) -> &str {
+        self.video_id.as_str()
+    }
+
+    pub fn markers(&self) -> &[StreamMarker] {
+        self.markers.as_slice()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserStreamMarkers {
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    videos: Vec<VideoStreamMarkers>,
+}
+
+impl UserStreamMarkers {
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn videos(&self) -> &[VideoStreamMarkers] {
+        self.videos.as_slice()
+    }
+}
+
+#[derive(Deserialize)]
+struct StreamKey {
+    stream_key: Secret,
+}
+
+#[derive(Serialize)]
+struct CreateStreamMarkerBody {
+    user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl ApiClient {
+    pub async fn get_streams(&self, filter: &StreamFilter, page: &PageRequest) -> Result<Page<Stream>> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("streams");
+        for user_id in &filter.user_ids {
+            builder = builder.with_param("user_id", user_id.as_str());
+        }
+        for user_login in &filter.user_logins {
+            builder = builder.with_param("user_login", user_login.as_str());
+        }
+        for game_id in &filter.game_ids {
+            builder = builder.with_param("game_id", game_id.as_str());
+        }
+        for language in &filter.languages {
+            builder = builder.with_param("language", language.as_str());
+        }
+        if let Some(stream_type) = filter.stream_type {
+            builder = builder.with_param("type", stream_type.as_str());
+        }
+        let response: HelixPaginatedResponse<Stream> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // streams of the channels the given user follows; needs that user's token
+    pub async fn get_followed_streams(&self, user_id: impl ToString, page: &PageRequest) -> Result<Page<Stream>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("streams/followed")
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::UserReadFollows)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<Stream> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    pub async fn get_stream_key(&self, broadcaster_id: impl ToString) -> Result<Secret> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("streams/key")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_scope(Scope::ChannelReadStreamKey)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<StreamKey> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .map(|key| key.stream_key)
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    // fails if the user is not live or has VODs disabled
+    pub async fn create_stream_marker(&self, user_id: impl ToString, description: Option<String>) -> Result<StreamMarker> {
+        let call = TwitchApiCall::<CreateStreamMarkerBody>::builder()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("streams/markers")
+            .with_method(Method::POST)
+            .with_body(CreateStreamMarkerBody {
+                user_id: user_id.to_string(),
+                description,
+            })
+            .with_scope(Scope::ChannelManageBroadcast)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<StreamMarker> = self.call_api(call).await?;
+        response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)).into())
+    }
+
+    // markers of the user's most recent VOD
+    pub async fn get_stream_markers_for_user(&self, user_id: impl ToString, page: &PageRequest) -> Result<Page<UserStreamMarkers>> {
+        self.get_stream_markers("user_id", user_id.to_string(), page).await
+    }
+
+    pub async fn get_stream_markers_for_video(&self, video_id: impl ToString, page: &PageRequest) -> Result<Page<UserStreamMarkers>> {
+        self.get_stream_markers("video_id", video_id.to_string(), page).await
+    }
+
+    async fn get_stream_markers(&self, key: &'static str, value: String, page: &PageRequest) -> Result<Page<UserStreamMarkers>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("streams/markers")
+            .with_param(key, value)
+            .with_any_scope(vec![Scope::UserReadBroadcast, Scope::ChannelManageBroadcast])
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<UserStreamMarkers> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::helix::{HelixPaginatedResponse, Page, PageRequest};
+    use super::Stream;
+
+    #[test]
+    fn parses_streams_page_and_templates_thumbnails() {
+        let response: HelixPaginatedResponse<Stream> = serde_json::from_str(r#"{
+            "data": [{
+                "id": "40952121085",
+                "user_id": "101051819",
+                "user_login": "afro",
+                "user_name": "Afro",
+                "game_id": "32982",
+                "game_name": "Grand Theft Auto V",
+                "type": "live",
+                "title": "Jacob: Digital Den Laptops & Tablets",
+                "tags": ["English"],
+                "viewer_count": 1490,
+                "started_at": "2021-03-10T03:18:11Z",
+                "language": "en",
+                "thumbnail_url": "https://static-cdn.jtvnw.net/previews-ttv/live_user_afro-{width}x{height}.jpg",
+                "tag_ids": [],
+                "is_mature": false
+            }],
+            "pagination": {"cursor": "eyJiIjp7IkN1cnNvciI6ImV5SnpJam94TXpNd05qUTJNVEF4TmpBeE5qRTJNak14TnpZc0ltUWlPbVpoYkhObExDSjBJanAwY25WbGZRPT0ifX0"}
+        }"#).unwrap();
+        let page = Page::new(response, &PageRequest::new().with_first(1));
+        let stream = &page.data()[0];
+        assert!(stream.is_live());
+        assert_eq!(stream.viewer_count(), 1490);
+        assert_eq!(stream.thumbnail_url(1280, 720), "https://static-cdn.jtvnw.net/previews-ttv/live_user_afro-1280x720.jpg");
+        assert!(page.next_page().is_some());
+    }
+}
@@ -0,0 +1,286 @@
+use http::StatusCode;
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{ApiClient, HttpStatusError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{HelixPaginatedResponse, HelixResponse, Page, PageRequest, MAX_BATCH_SIZE};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SubTier {
+    Prime,
+    Tier1,
+    Tier2,
+    Tier3,
+    // a tier this version does not know about yet
+    Unknown,
+}
+
+impl SubTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubTier::Prime => "Prime",
+            SubTier::Tier1 => "1000",
+            SubTier::Tier2 => "2000",
+            SubTier::Tier3 => "3000",
+            SubTier::Unknown => "unknown",
+        }
+    }
+
+    // what the tier counts for in the channel's sub points
+    pub fn points(&self) -> u64 {
+        match self {
+            SubTier::Prime | SubTier::Tier1 => 1,
+            SubTier::Tier2 => 2,
+            SubTier::Tier3 => 6,
+            SubTier::Unknown => 0,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SubTier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let tier = String::deserialize(deserializer)?;
+        match tier.as_str() {
+            "1000" => Ok(SubTier::Tier1),
+            "2000" => Ok(SubTier::Tier2),
+            "3000" => Ok(SubTier::Tier3),
+            "prime" | "Prime" => Ok(SubTier::Prime),
+            _ => Ok(SubTier::Unknown)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gifter {
+    id: String,
+    login: String,
+    name: String,
+}
+
+impl Gifter {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn login(&self) -> &str {
+        self.login.as_str()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct GifterData {
+    #[serde(default)]
+    gifter_id: Option<String>,
+    #[serde(default)]
+    gifter_login: Option<String>,
+    #[serde(default)]
+    gifter_name: Option<String>,
+}
+
+impl GifterData {
+    // Twitch sends empty strings for subs that were not gifted, or were gifted anonymously
+    fn gifter(&self) -> Option<Gifter> {
+        match &self.gifter_id {
+            Some(id) if !id.is_empty() => Some(Gifter {
+                id: id.clone(),
+                login: self.gifter_login.clone().unwrap_or_default(),
+                name: self.gifter_name.clone().unwrap_or_default(),
+            }),
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Subscription {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    tier: SubTier,
+    plan_name: String,
+    is_gift: bool,
+    #[serde(flatten)]
+    gifter: GifterData,
+}
+
+impl Subscription {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn tier(&self) -> SubTier {
+        self.tier
+    }
+
+    pub fn plan_name(&self) -> &str {
+        self.plan_name.as_str()
+    }
+
+    pub fn is_gift(&self) -> bool {
+        self.is_gift
+    }
+
+    pub fn gifter(&self) -> Option<Gifter> {
+        self.gifter.gifter()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserSubscription {
+    broadcaster_id: String,
+    broadcaster_login: String,
+    broadcaster_name: String,
+    tier: SubTier,
+    is_gift: bool,
+    #[serde(flatten)]
+    gifter: GifterData,
+}
+
+impl UserSubscription {
+    pub fn broadcaster_id(&self) -> &str {
+        self.broadcaster_id.as_str()
+    }
+
+    pub fn broadcaster_login(&self) -> &str {
+        self.broadcaster_login.as_str()
+    }
+
+    pub fn broadcaster_name(&self) -> &str {
+        self.broadcaster_name.as_str()
+    }
+
+    pub fn tier(&self) -> SubTier {
+        self.tier
+    }
+
+    pub fn is_gift(&self) -> bool {
+        self.is_gift
+    }
+
+    pub fn gifter(&self) -> Option<Gifter> {
+        self.gifter.gifter()
+    }
+}
+
+impl ApiClient {
+    // the page also reports the total number of subs and the channel's sub points
+    pub async fn get_broadcaster_subscriptions(&self, broadcaster_id: impl ToString, page: &PageRequest) -> Result<Page<Subscription>> {
+        let builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("subscriptions")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_scope(Scope::ChannelReadSubscriptions)
+            .with_token_type(TokenType::User);
+        let response: HelixPaginatedResponse<Subscription> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // users that are not subscribed are left out of the result
+    pub async fn get_subscriptions_for_users(&self, broadcaster_id: impl ToString, user_ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<Subscription>> {
+        let broadcaster_id = broadcaster_id.to_string();
+        let user_ids: Vec<String> = user_ids.into_iter().map(|id| id.to_string()).collect();
+        let mut subscriptions = Vec::with_capacity(user_ids.len());
+        for chunk in user_ids.chunks(MAX_BATCH_SIZE) {
+            let builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("subscriptions")
+                .with_param("broadcaster_id", broadcaster_id.as_str())
+                .with_scope(Scope::ChannelReadSubscriptions)
+                .with_token_type(TokenType::User);
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("user_id", id.as_str()));
+            let response: HelixResponse<Subscription> = self.call_api(builder.build()?).await?;
+            subscriptions.extend(response.data);
+        }
+        Ok(subscriptions)
+    }
+
+    // checked with the user's own token; None means the user is not subscribed
+    pub async fn check_user_subscription(&self, user_id: impl ToString, broadcaster_id: impl ToString) -> Result<Option<UserSubscription>> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("subscriptions/user")
+            .with_param("broadcaster_id", broadcaster_id.to_string())
+            .with_param("user_id", user_id.to_string())
+            .with_scope(Scope::UserReadSubscriptions)
+            .with_token_type(TokenType::User)
+            .build()?;
+        match self.call_api::<HelixResponse<UserSubscription>, _>(call).await {
+            Ok(response) => Ok(response.data.into_iter().next()),
+            // Twitch answers "not subscribed" with a 404
+            Err(e) => match e.downcast_ref::<HttpStatusError>() {
+                Some(error) if error.status() == StatusCode::NOT_FOUND => Ok(None),
+                _ => Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SubTier, Subscription};
+
+    #[test]
+    fn parses_tiers_and_gifters() {
+        let subscription: Subscription = serde_json::from_str(r#"{
+            "broadcaster_id": "141981764",
+            "broadcaster_login": "twitchdev",
+            "broadcaster_name": "TwitchDev",
+            "gifter_id": "12826",
+            "gifter_login": "twitch",
+            "gifter_name": "Twitch",
+            "is_gift": true,
+            "tier": "2000",
+            "plan_name": "Channel Subscription (twitchdev)",
+            "user_id": "527115020",
+            "user_name": "twitchgaming",
+            "user_login": "twitchgaming"
+        }"#).unwrap();
+        assert_eq!(subscription.tier(), SubTier::Tier2);
+        assert_eq!(serde_json::from_str::<SubTier>(r#""4000""#).unwrap(), SubTier::Unknown);
+        assert_eq!(subscription.gifter().unwrap().login(), "twitch");
+
+        let subscription: Subscription = serde_json::from_str(r#"{
+            "broadcaster_id": "141981764",
+            "broadcaster_login": "twitchdev",
+            "broadcaster_name": "TwitchDev",
+            "gifter_id": "",
+            "gifter_login": "",
+            "gifter_name": "",
+            "is_gift": false,
+            "tier": "1000",
+            "plan_name": "Channel Subscription (twitchdev)",
+            "user_id": "527115020",
+            "user_name": "twitchgaming",
+            "user_login": "twitchgaming"
+        }"#).unwrap();
+        assert_eq!(subscription.gifter(), None);
+    }
+}
@@ -0,0 +1,236 @@
+use chrono::{DateTime, Utc};
+use http::Method;
+
+use crate::api::{ApiClient, EmptyResponseError, TwitchApiCall, TwitchApiCallType};
+use crate::api::helix::{CacheEntry, HelixResponse};
+use crate::auth::{AccessToken, Scope, TokenType};
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserType {
+    #[serde(rename = "")]
+    Normal,
+    Admin,
+    GlobalMod,
+    Staff,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcasterType {
+    #[serde(rename = "")]
+    Normal,
+    Affiliate,
+    Partner,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct User {
+    id: String,
+    login: String,
+    display_name: String,
+    #[serde(rename = "type")]
+    user_type: UserType,
+    broadcaster_type: BroadcasterType,
+    description: String,
+    profile_image_url: String,
+    offline_image_url: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    email: Option<String>,
+}
+
+impl User {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn login(&self) -> &str {
+        self.login.as_str()
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_str()
+    }
+
+    pub fn user_type(&self) -> UserType {
+        self.user_type
+    }
+
+    pub fn broadcaster_type(&self) -> BroadcasterType {
+        self.broadcaster_type
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn profile_image_url(&self) -> &str {
+        self.profile_image_url.as_str()
+    }
+
+    // empty if the user has not uploaded one
+    pub fn offline_image_url(&self) -> &str {
+        self.offline_image_url.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // only present for the token's own user with the user:read:email scope, so get_me is the way to get it
+    // (it skips the user cache for such tokens)
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+}
+
+impl CacheEntry for User {
+    fn cache_keys(&self) -> Vec<(&'static str, String)> {
+        vec![("id", self.id.clone()), ("login", self.login.clone())]
+    }
+
+    // the email is only visible to the user themselves
+    fn for_cache(&self) -> Self {
+        Self {
+            email: None,
+            ..self.clone()
+        }
+    }
+}
+
+impl ApiClient {
+    // a validated token already knows who it belongs to, so a cached user saves the request -
+    // unless the token may read the email, which cached users come without
+    fn cached_me(&self, token: &AccessToken) -> Option<User> {
+        if token.scopes().contains(&Scope::UserReadEmail) {
+            return None;
+        }
+        token.user_id().and_then(|id| self.cache().users.get("id", id))
+    }
+
+    pub async fn get_me(&self) -> Result<User> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("users")
+            .with_token_type(TokenType::User)
+            .build()?;
+        let (client_id, token) = self.credentials_for_call(&call).await?;
+        if let Some(user) = self.cached_me(&token) {
+            return Ok(user);
+        }
+        let url = call.full_url().to_string();
+        let response: HelixResponse<User> = Self::call_api_with_credentials(call, client_id, token.access_token()).await?;
+
+        let user = response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)))?;
+        self.cache().users.insert(&user);
+        Ok(user)
+    }
+
+    pub async fn get_user_by_id(&self, id: impl ToString) -> Result<Option<User>> {
+        Ok(self.get_users_by_ids(vec![id.to_string()]).await?.into_iter().next())
+    }
+
+    pub async fn get_user_by_login(&self, login: impl ToString) -> Result<Option<User>> {
+        Ok(self.get_users_by_logins(vec![login.to_string()]).await?.into_iter().next())
+    }
+
+    pub async fn get_users_by_ids(&self, ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<User>> {
+        self.get_users(ids.into_iter().map(|id| ("id", id.to_string())).collect()).await
+    }
+
+    pub async fn get_users_by_logins(&self, logins: impl IntoIterator<Item = impl ToString>) -> Result<Vec<User>> {
+        self.get_users(logins.into_iter().map(|login| ("login", login.to_string().to_lowercase())).collect()).await
+    }
+
+    // users that do not exist (anymore) are silently left out of the result
+    async fn get_users(&self, params: Vec<(&'static str, String)>) -> Result<Vec<User>> {
+        self.batched_lookup("users", &self.cache().users, params).await
+    }
+
+    pub async fn update_user_description(&self, description: impl ToString) -> Result<User> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("users")
+            .with_method(Method::PUT)
+            .with_param("description", description.to_string())
+            .with_scope(Scope::UserEdit)
+            .with_token_type(TokenType::User)
+            .build()?;
+        let url = call.full_url().to_string();
+        let response: HelixResponse<User> = self.call_api(call).await?;
+
+        let user = response.data.into_iter().next()
+            .ok_or_else(|| Box::new(EmptyResponseError::new(url)))?;
+        self.cache().users.insert(&user);
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::api::ApiClient;
+    use crate::auth::{AccessToken, AccessTokenData, StaticAuthProvider};
+    use super::{BroadcasterType, User, UserType};
+
+    fn user_with_email() -> User {
+        serde_json::from_str(r#"{
+            "id": "141981764",
+            "login": "twitchdev",
+            "display_name": "TwitchDev",
+            "type": "",
+            "broadcaster_type": "partner",
+            "description": "",
+            "profile_image_url": "",
+            "offline_image_url": "",
+            "created_at": "2016-12-14T20:32:28Z",
+            "email": "dev@example.com"
+        }"#).unwrap()
+    }
+
+    fn validated_token(scopes: &str) -> AccessToken {
+        AccessToken::new(serde_json::from_str::<AccessTokenData>(&format!(
+            r#"{{"access_token":"token","refresh_token":null,"expires_in":null,"scope":{},"user_id":"141981764"}}"#,
+            scopes
+        )).unwrap())
+    }
+
+    #[test]
+    fn does_not_serve_get_me_from_the_cache_when_the_email_is_readable() {
+        let client = ApiClient::new(Arc::new(StaticAuthProvider::new("client-id".to_string(), "token")));
+        client.cache().users.insert(&user_with_email());
+
+        let cached = client.cached_me(&validated_token("[]")).unwrap();
+        assert_eq!(cached.email(), None);
+        // with the scope the user is fetched again, so the email comes along
+        assert!(client.cached_me(&validated_token(r#"["user:read:email"]"#)).is_none());
+    }
+
+    #[test]
+    fn parses_helix_users() {
+        let user: User = serde_json::from_str(r#"{
+            "id": "141981764",
+            "login": "twitchdev",
+            "display_name": "TwitchDev",
+            "type": "",
+            "broadcaster_type": "partner",
+            "description": "Supporting third-party developers building Twitch integrations.",
+            "profile_image_url": "https://static-cdn.jtvnw.net/jtv_user_pictures/profile_image-300x300.png",
+            "offline_image_url": "",
+            "view_count": 5980557,
+            "created_at": "2016-12-14T20:32:28Z"
+        }"#).unwrap();
+        assert_eq!(user.display_name(), "TwitchDev");
+        assert_eq!(user.user_type(), UserType::Normal);
+        assert_eq!(user.broadcaster_type(), BroadcasterType::Partner);
+        assert_eq!(user.created_at().timestamp(), 1481747548);
+        assert_eq!(user.email(), None);
+    }
+}
@@ -0,0 +1,358 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use http::Method;
+use serde::{Deserialize, Deserializer};
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType, UnknownValueError};
+use crate::api::helix::{template_image_url, HelixPaginatedResponse, HelixResponse, Page, PageRequest};
+use crate::auth::{Scope, TokenType};
+use crate::util::Result;
+
+// DELETE /videos takes at most this many ids at once
+const MAX_DELETE_BATCH_SIZE: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoType {
+    All,
+    Archive,
+    Highlight,
+    Upload,
+    // types Twitch added later; filtering by it fails with an UnknownValueError
+    #[serde(other)]
+    Unknown,
+}
+
+impl VideoType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoType::All => "all",
+            VideoType::Archive => "archive",
+            VideoType::Highlight => "highlight",
+            VideoType::Upload => "upload",
+            VideoType::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoPeriod {
+    All,
+    Day,
+    Week,
+    Month,
+}
+
+impl VideoPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoPeriod::All => "all",
+            VideoPeriod::Day => "day",
+            VideoPeriod::Week => "week",
+            VideoPeriod::Month => "month",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VideoSort {
+    Time,
+    Trending,
+    Views,
+}
+
+impl VideoSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoSort::Time => "time",
+            VideoSort::Trending => "trending",
+            VideoSort::Views => "views",
+        }
+    }
+}
+
+// parses the format Twitch uses for video durations, e.g. "3h8m33s"
+pub(crate) fn parse_duration(duration: &str) -> Option<Duration> {
+    let mut secs: u64 = 0;
+    let mut number = String::new();
+    for c in duration.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' | 's' => {
+                let value: u64 = number.parse().ok()?;
+                let unit = match c {
+                    'h' => 3600,
+                    'm' => 60,
+                    _ => 1,
+                };
+                secs = secs.checked_add(value.checked_mul(unit)?)?;
+                number.clear();
+            }
+            _ => return None
+        }
+    }
+    match number.is_empty() && !duration.is_empty() {
+        true => Some(Duration::from_secs(secs)),
+        false => None
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Duration, D::Error> {
+    let duration = String::deserialize(deserializer)?;
+    parse_duration(duration.as_str())
+        .ok_or_else(|| serde::de::Error::custom(format!("invalid duration {}", duration)))
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct MutedSegment {
+    duration: u64,
+    offset: u64,
+}
+
+impl MutedSegment {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration)
+    }
+
+    // from the start of the video
+    pub fn offset(&self) -> Duration {
+        Duration::from_secs(self.offset)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Video {
+    id: String,
+    #[serde(default)]
+    stream_id: Option<String>,
+    user_id: String,
+    user_login: String,
+    user_name: String,
+    title: String,
+    description: String,
+    created_at: DateTime<Utc>,
+    published_at: DateTime<Utc>,
+    url: String,
+    thumbnail_url: String,
+    viewable: String,
+    view_count: u64,
+    language: String,
+    #[serde(rename = "type")]
+    video_type: VideoType,
+    #[serde(deserialize_with = "deserialize_duration")]
+    duration: Duration,
+    #[serde(default)]
+    muted_segments: Option<Vec<MutedSegment>>,
+}
+
+impl Video {
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    // only set for archives
+    pub fn stream_id(&self) -> Option<&str> {
+        self.stream_id.as_deref()
+    }
+
+    pub fn user_id(&self) -> &str {
+        self.user_id.as_str()
+    }
+
+    pub fn user_login(&self) -> &str {
+        self.user_login.as_str()
+    }
+
+    pub fn user_name(&self) -> &str {
+        self.user_name.as_str()
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+
+    pub fn description(&self) -> &str {
+        self.description.as_str()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn published_at(&self) -> DateTime<Utc> {
+        self.published_at
+    }
+
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    // empty while an archive is still being recorded
+    pub fn thumbnail_url(&self, width: u32, height: u32) -> String {
+        template_image_url(self.thumbnail_url.as_str(), width, height)
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.viewable == "public"
+    }
+
+    pub fn view_count(&self) -> u64 {
+        self.view_count
+    }
+
+    pub fn language(&self) -> &str {
+        self.language.as_str()
+    }
+
+    pub fn video_type(&self) -> VideoType {
+        self.video_type
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn muted_segments(&self) -> &[MutedSegment] {
+        self.muted_segments.as_deref().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug)]
+enum VideoSource {
+    Ids(Vec<String>),
+    User(String),
+    Game(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct VideoFilter {
+    source: VideoSource,
+    language: Option<String>,
+    period: Option<VideoPeriod>,
+    sort: Option<VideoSort>,
+    video_type: Option<VideoType>,
+}
+
+impl VideoFilter {
+    fn new(source: VideoSource) -> Self {
+        Self {
+            source,
+            language: None,
+            period: None,
+            sort: None,
+            video_type: None,
+        }
+    }
+
+    // up to 100 ids; the other filters are ignored for lookups by id
+    pub fn for_ids(ids: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::new(VideoSource::Ids(ids.into_iter().map(Into::into).collect()))
+    }
+
+    pub fn for_user(user_id: impl Into<String>) -> Self {
+        Self::new(VideoSource::User(user_id.into()))
+    }
+
+    pub fn for_game(game_id: impl Into<String>) -> Self {
+        Self::new(VideoSource::Game(game_id.into()))
+    }
+
+    // only applies to lookups by game
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn with_period(mut self, period: VideoPeriod) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    pub fn with_sort(mut self, sort: VideoSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn with_type(mut self, video_type: VideoType) -> Self {
+        self.video_type = Some(video_type);
+        self
+    }
+}
+
+impl ApiClient {
+    pub async fn get_videos(&self, filter: &VideoFilter, page: &PageRequest) -> Result<Page<Video>> {
+        let mut builder = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Helix)
+            .with_url("videos");
+        builder = match &filter.source {
+            VideoSource::Ids(ids) => ids.iter().fold(builder, |builder, id| builder.with_param("id", id.as_str())),
+            VideoSource::User(user_id) => builder.with_param("user_id", user_id.as_str()),
+            VideoSource::Game(game_id) => builder.with_param("game_id", game_id.as_str()),
+        };
+        if let Some(language) = &filter.language {
+            builder = builder.with_param("language", language.as_str());
+        }
+        if let Some(period) = filter.period {
+            builder = builder.with_param("period", period.as_str());
+        }
+        if let Some(sort) = filter.sort {
+            builder = builder.with_param("sort", sort.as_str());
+        }
+        if let Some(video_type) = filter.video_type {
+            if video_type == VideoType::Unknown {
+                return Err(Box::new(UnknownValueError::new("type")));
+            }
+            builder = builder.with_param("type", video_type.as_str());
+        }
+        let response: HelixPaginatedResponse<Video> = self.call_api(page.apply(builder).build()?).await?;
+        Ok(Page::new(response, page))
+    }
+
+    // returns the ids that were actually deleted; more than 5 ids are split into several requests
+    pub async fn delete_videos(&self, ids: impl IntoIterator<Item = impl ToString>) -> Result<Vec<String>> {
+        let ids: Vec<String> = ids.into_iter().map(|id| id.to_string()).collect();
+        let mut deleted = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_DELETE_BATCH_SIZE) {
+            let builder = TwitchApiCall::builder_empty()
+                .with_call_type(TwitchApiCallType::Helix)
+                .with_url("videos")
+                .with_method(Method::DELETE)
+                .with_scope(Scope::ChannelManageVideos)
+                .with_token_type(TokenType::User);
+            let builder = chunk.iter().fold(builder, |builder, id| builder.with_param("id", id.as_str()));
+            let response: HelixResponse<String> = self.call_api(builder.build()?).await?;
+            deleted.extend(response.data);
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use crate::api::{ApiClient, UnknownValueError};
+    use crate::api::helix::PageRequest;
+    use crate::auth::StaticAuthProvider;
+    use super::{parse_duration, VideoFilter, VideoType};
+
+    #[test]
+    fn parses_twitch_durations() {
+        assert_eq!(parse_duration("3h8m33s"), Some(Duration::from_secs(3 * 3600 + 8 * 60 + 33)));
+        assert_eq!(parse_duration("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("1m"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("12"), None);
+        assert_eq!(parse_duration("1x"), None);
+        assert_eq!(parse_duration("18446744073709551615h"), None);
+        assert_eq!(parse_duration("18446744073709551615s1s"), None);
+    }
+
+    #[tokio::test]
+    async fn refuses_to_filter_by_unknown_types() {
+        let client = ApiClient::new(Arc::new(StaticAuthProvider::new("client-id".to_string(), "token")));
+        let filter = VideoFilter::for_user("141981764").with_type(VideoType::Unknown);
+        let error = client.get_videos(&filter, &PageRequest::new()).await.unwrap_err();
+        assert!(error.downcast_ref::<UnknownValueError>().is_some());
+    }
+}
@@ -1,7 +1,9 @@
 mod api_call;
 mod client;
+mod helix;
 mod token_info;
 
 pub use api_call::{TwitchApiCall, TwitchAPICallBuilder, TwitchApiCallType};
-pub use client::ApiClient;
+pub use client::{ApiClient, EmptyResponseError, HttpStatusError, TokenTypeError, UnknownValueError};
+pub use helix::{AnnouncementColor, AutoModSettings, AutoModStatus, BannedUser, BitsLeaderboard, BitsLeaderboardEntry, BitsLeaderboardPeriod, BitsLeaderboardQuery, BlockedTerm, BroadcasterType, ChannelEditor, ChannelFollower, ChannelInformation, ChannelSearchResult, ChannelUpdate, ChannelUser, ChatBadge, ChatBadgeSet, ChatSettings, ChatSettingsUpdate, Chatter, Cheermote, CheermoteFormat, CheermoteScale, CheermoteTheme, CheermoteTier, Cheermotes, Clip, ClipFilter, ClipTimeoutError, ContentClassificationLabel, CreatedClip, CustomReward, CustomRewardImage, CustomRewardRedemption, CustomRewardSettings, Emote, EmoteFormat, EmoteScale, EmoteTheme, FollowedChannel, Game, Gifter, ModeratedChannel, MutedSegment, OutcomeColor, Page, PageRequest, Poll, PollChoice, PollRequest, PollStatus, Prediction, PredictionOutcome, PredictionStatus, Predictor, RedemptionFilter, RedemptionReward, RedemptionSort, RedemptionStatus, ResolvedCheer, RewardNotManageableError, Stream, StreamFilter, StreamMarker, StreamType, SubTier, Subscription, UnbanRequest, UnbanRequestStatus, User, UserBan, UserChatColor, UserStreamMarkers, UserSubscription, UserType, Video, VideoFilter, VideoPeriod, VideoSort, VideoStreamMarkers, VideoType, DEFAULT_CLIP_TIMEOUT};
 pub use token_info::{TokenInfo, TokenInfoData};
\ No newline at end of file
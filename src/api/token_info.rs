@@ -1,11 +1,14 @@
 use std::time::{Duration, SystemTime};
 
+use crate::auth::Scope;
+
 #[derive(Clone, Deserialize, Debug)]
 pub struct TokenInfoData {
     client_id: String,
-    login: String,
-    scopes: Vec<String>,
-    user_id: String,
+    // app tokens have no user attached and come back without these
+    login: Option<String>,
+    scopes: Option<Vec<Scope>>,
+    user_id: Option<String>,
     expires_in: Option<u64>
 }
 
@@ -26,8 +29,24 @@ impl TokenInfo {
         self.data.client_id.as_str()
     }
 
-    pub fn scopes(&self) -> &[String] {
-        self.data.scopes.as_slice()
+    pub fn login(&self) -> Option<&str> {
+        self.data.login.as_deref()
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        self.data.user_id.as_deref()
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        self.data.scopes.as_ref().map_or(&[], Vec::as_slice)
+    }
+
+    pub fn expires_in(&self) -> Option<u64> {
+        self.data.expires_in
+    }
+
+    pub fn obtainment_date(&self) -> SystemTime {
+        self.obtainment_date
     }
 
     pub fn expiry_date(&self) -> Option<SystemTime> {
@@ -1,15 +1,56 @@
 use std::option::Option;
 use std::time::SystemTime;
+use std::fmt::Display;
 
-#[derive(Clone, Deserialize, Debug)]
+use crate::api::TokenInfo;
+use crate::auth::{Scope, Secret};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenType {
+    App,
+    User,
+    Extension,
+}
+
+impl Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TokenType::App => f.write_str("app"),
+            TokenType::User => f.write_str("user"),
+            TokenType::Extension => f.write_str("extension"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct AccessTokenData {
-    access_token: String,
-    refresh_token: Option<String>,
+    access_token: Secret,
+    refresh_token: Option<Secret>,
     expires_in: Option<u64>,
-    scope: Vec<String>,
+    scope: Vec<Scope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id_token: Option<Secret>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    login: Option<String>,
+}
+
+impl AccessTokenData {
+    pub fn new(access_token: impl Into<Secret>, refresh_token: Option<Secret>, expires_in: Option<u64>, scope: Vec<Scope>) -> Self {
+        Self {
+            access_token: access_token.into(),
+            refresh_token,
+            expires_in,
+            scope,
+            id_token: None,
+            user_id: None,
+            login: None,
+        }
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct AccessToken {
     data: AccessTokenData,
     obtainment_date: SystemTime,
@@ -26,34 +67,43 @@ impl AccessToken {
     pub fn empty() -> Self {
         Self {
             data: AccessTokenData {
-                access_token: "".to_string(),
+                access_token: Secret::from(""),
                 refresh_token: None,
                 expires_in: None,
                 scope: vec![],
+                id_token: None,
+                user_id: None,
+                login: None,
             },
             obtainment_date: SystemTime::now(),
         }
     }
 
-    pub(crate) fn with_access_token(access_token: String) -> Self {
+    pub(crate) fn with_access_token(access_token: impl Into<Secret>) -> Self {
         Self {
             data: AccessTokenData {
-                access_token,
+                access_token: access_token.into(),
                 refresh_token: None,
                 expires_in: None,
                 scope: vec![],
+                id_token: None,
+                user_id: None,
+                login: None,
             },
             obtainment_date: SystemTime::now(),
         }
     }
 
-    pub(crate) fn with_access_token_and_scopes(access_token: String, scopes: Vec<String>) -> Self {
+    pub(crate) fn with_access_token_and_scopes(access_token: impl Into<Secret>, scopes: Vec<Scope>) -> Self {
         Self {
             data: AccessTokenData {
-                access_token,
+                access_token: access_token.into(),
                 refresh_token: None,
                 expires_in: None,
                 scope: scopes,
+                id_token: None,
+                user_id: None,
+                login: None,
             },
             obtainment_date: SystemTime::now(),
         }
@@ -66,18 +116,69 @@ impl AccessToken {
         }
     }
 
-    pub fn access_token(&self) -> &str {
-        self.data.access_token.as_str()
+    pub fn with_token_info(&self, token_info: &TokenInfo) -> Self {
+        Self {
+            data: AccessTokenData {
+                expires_in: token_info.expires_in(),
+                scope: token_info.scopes().to_vec(),
+                user_id: token_info.user_id().map(str::to_string),
+                login: token_info.login().map(str::to_string),
+                ..self.data.clone()
+            },
+            obtainment_date: token_info.obtainment_date(),
+        }
+    }
+
+    // for tokens whose user is known from elsewhere, e.g. refreshed ones
+    pub(crate) fn with_user(&self, user_id: impl Into<String>, login: Option<&str>) -> Self {
+        Self {
+            data: AccessTokenData {
+                user_id: Some(user_id.into()),
+                login: login.map(str::to_string),
+                ..self.data.clone()
+            },
+            obtainment_date: self.obtainment_date,
+        }
+    }
+
+    pub fn data(&self) -> &AccessTokenData {
+        &self.data
+    }
+
+    pub fn obtainment_date(&self) -> SystemTime {
+        self.obtainment_date
+    }
+
+    pub fn expires_in(&self) -> Option<u64> {
+        self.data.expires_in
+    }
+
+    pub fn access_token(&self) -> &Secret {
+        &self.data.access_token
     }
 
-    pub fn refresh_token(&self) -> Option<&str> {
-        self.data.refresh_token.as_ref().map(String::as_str)
+    pub fn refresh_token(&self) -> Option<&Secret> {
+        self.data.refresh_token.as_ref()
     }
 
-    pub fn scopes(&self) -> &[String] {
+    pub fn scopes(&self) -> &[Scope] {
         &self.data.scope
     }
 
+    // only known once the token has been validated
+    pub fn user_id(&self) -> Option<&str> {
+        self.data.user_id.as_deref()
+    }
+
+    pub fn login(&self) -> Option<&str> {
+        self.data.login.as_deref()
+    }
+
+    // only present for tokens obtained through the OpenID Connect authorization code flow
+    pub fn id_token(&self) -> Option<&Secret> {
+        self.data.id_token.as_ref()
+    }
+
     pub fn is_expired(&self) -> bool {
         match self.data.expires_in {
             Some(secs) => SystemTime::now().duration_since(self.obtainment_date).unwrap().as_secs() > secs,
@@ -0,0 +1,139 @@
+use http::Method;
+use url::Url;
+
+use crate::api::{ApiClient, TwitchApiCall, TwitchApiCallType};
+use crate::auth::{AccessToken, Scope, Secret};
+use crate::util::Result;
+
+const AUTHORIZE_URL: &str = "https://id.twitch.tv/oauth2/authorize";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OidcClaim {
+    Email,
+    EmailVerified,
+    Picture,
+    PreferredUsername,
+    UpdatedAt,
+}
+
+impl OidcClaim {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OidcClaim::Email => "email",
+            OidcClaim::EmailVerified => "email_verified",
+            OidcClaim::Picture => "picture",
+            OidcClaim::PreferredUsername => "preferred_username",
+            OidcClaim::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OidcClaimsRequest {
+    id_token: Vec<OidcClaim>,
+    userinfo: Vec<OidcClaim>,
+}
+
+impl OidcClaimsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_id_token_claim(mut self, claim: OidcClaim) -> Self {
+        self.id_token.push(claim);
+        self
+    }
+
+    pub fn with_userinfo_claim(mut self, claim: OidcClaim) -> Self {
+        self.userinfo.push(claim);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.id_token.is_empty() && self.userinfo.is_empty()
+    }
+
+    // Twitch expects the requested claims as keys with null values, grouped by where they should show up
+    fn to_json(&self) -> String {
+        let group = |claims: &[OidcClaim]| claims.iter()
+            .map(|claim| (claim.as_str().to_string(), serde_json::Value::Null))
+            .collect::<serde_json::Map<_, _>>();
+        let mut claims = serde_json::Map::new();
+        if !self.id_token.is_empty() {
+            claims.insert("id_token".to_string(), group(&self.id_token).into());
+        }
+        if !self.userinfo.is_empty() {
+            claims.insert("userinfo".to_string(), group(&self.userinfo).into());
+        }
+        serde_json::Value::Object(claims).to_string()
+    }
+}
+
+pub(crate) fn authorize_url(client_id: &str, redirect_uri: &str, scopes: &[Scope], extra_params: &[(&str, &str)]) -> Url {
+    let scope = scopes.iter().map(Scope::as_str).collect::<Vec<_>>().join(" ");
+    let params = [
+        ("client_id", client_id),
+        ("redirect_uri", redirect_uri),
+        ("response_type", "code"),
+        ("scope", scope.as_str()),
+    ];
+    Url::parse_with_params(AUTHORIZE_URL, params.iter().chain(extra_params.iter())).unwrap()
+}
+
+pub struct AuthorizationCodeFlow {
+    client_id: String,
+    client_secret: Secret,
+    redirect_uri: String,
+}
+
+impl AuthorizationCodeFlow {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<Secret>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: redirect_uri.into(),
+        }
+    }
+
+    pub fn client_id(&self) -> &str {
+        self.client_id.as_str()
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        self.redirect_uri.as_str()
+    }
+
+    pub fn authorize_url(&self, scopes: &[Scope], state: &str) -> Url {
+        authorize_url(self.client_id.as_str(), self.redirect_uri.as_str(), scopes, &[("state", state)])
+    }
+
+    pub fn openid_authorize_url(&self, scopes: &[Scope], state: &str, nonce: &str, claims: &OidcClaimsRequest) -> Url {
+        let mut scopes = scopes.to_vec();
+        if !scopes.contains(&Scope::OpenId) {
+            scopes.insert(0, Scope::OpenId);
+        }
+        let claims_json = claims.to_json();
+        let mut params = vec![("state", state), ("nonce", nonce)];
+        if !claims.is_empty() {
+            params.push(("claims", claims_json.as_str()));
+        }
+        authorize_url(self.client_id.as_str(), self.redirect_uri.as_str(), &scopes, &params)
+    }
+
+    pub async fn exchange_code(&self, code: impl ToString) -> Result<AccessToken> {
+        let call = TwitchApiCall::builder_empty()
+            .with_call_type(TwitchApiCallType::Auth)
+            .with_url("token")
+            .with_method(Method::POST)
+            .with_form_params()
+            .with_param("grant_type", "authorization_code")
+            .with_param("client_id", self.client_id.as_str())
+            .with_param("client_secret", self.client_secret.expose())
+            .with_param("redirect_uri", self.redirect_uri.as_str())
+            .with_param("code", code.to_string())
+            .build()?;
+
+        let response = ApiClient::call_api_without_credentials(call).await?;
+        Ok(AccessToken::new(response))
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::auth::{AccessToken, Scope};
+
+#[derive(Clone, Debug)]
+pub enum AuthEvent {
+    // the user is only known for validated tokens and for tokens of a MultiUserAuthProvider
+    TokenRefreshed {
+        user_id: Option<String>,
+        token: AccessToken,
+    },
+    TokenInvalidated,
+    ScopesChanged {
+        user_id: Option<String>,
+        previous: Vec<Scope>,
+        current: Vec<Scope>,
+    },
+    // errors can not be cloned for every receiver, so only the message is passed along
+    RefreshFailed {
+        user_id: Option<String>,
+        message: String,
+    },
+}
+
+pub type AuthEventCallback = Arc<dyn Fn(&AuthEvent) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct AuthEvents {
+    sender: broadcast::Sender<AuthEvent>,
+    callbacks: Arc<Mutex<Vec<AuthEventCallback>>>,
+}
+
+impl AuthEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(16);
+        Self {
+            sender,
+            callbacks: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AuthEvent> {
+        self.sender.subscribe()
+    }
+
+    // callbacks run synchronously on whatever task changed the token, so they should not block
+    pub fn on_event(&self, callback: impl Fn(&AuthEvent) + Send + Sync + 'static) {
+        self.callbacks.lock().unwrap().push(Arc::new(callback));
+    }
+
+    pub fn emit(&self, event: AuthEvent) {
+        let callbacks = self.callbacks.lock().unwrap().clone();
+        for callback in callbacks {
+            callback(&event);
+        }
+        // having no receivers is not an error
+        let _ = self.sender.send(event);
+    }
+
+    // revalidating the same token only reports what actually changed; scope changes are only
+    // reported when the previous scopes were known, not when a token's scopes are first learned
+    pub(crate) fn token_changed(&self, previous: Option<&AccessToken>, previous_scopes: Option<&[Scope]>, current: &AccessToken) {
+        if previous.map(AccessToken::access_token) != Some(current.access_token()) {
+            self.emit(AuthEvent::TokenRefreshed {
+                user_id: current.user_id().map(str::to_string),
+                token: current.clone(),
+            });
+        }
+        if let Some(previous_scopes) = previous_scopes {
+            if previous_scopes != current.scopes() {
+                self.emit(AuthEvent::ScopesChanged {
+                    user_id: current.user_id().map(str::to_string),
+                    previous: previous_scopes.to_vec(),
+                    current: current.scopes().to_vec(),
+                });
+            }
+        }
+    }
+
+    pub(crate) fn refresh_failed(&self, user_id: Option<&str>, error: &(dyn std::error::Error + Send + Sync)) {
+        self.emit(AuthEvent::RefreshFailed {
+            user_id: user_id.map(str::to_string),
+            message: error.to_string(),
+        });
+    }
+}
+
+impl Default for AuthEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use crate::auth::{AccessToken, Scope};
+    use super::{AuthEvent, AuthEvents};
+
+    #[test]
+    fn reports_only_what_changed() {
+        let events = AuthEvents::new();
+        let mut receiver = events.subscribe();
+        let seen = Arc::new(Mutex::new(0));
+        let counter = seen.clone();
+        events.on_event(move |_| *counter.lock().unwrap() += 1);
+
+        let unvalidated = AccessToken::with_access_token_and_scopes("abc".to_string(), vec![]);
+        events.token_changed(None, None, &unvalidated);
+        let token = AccessToken::with_access_token_and_scopes("abc".to_string(), vec![Scope::ChatRead]);
+        events.token_changed(Some(&unvalidated), None, &token);
+        events.token_changed(Some(&token), Some(token.scopes()), &token.clone());
+        let upgraded = AccessToken::with_access_token_and_scopes("abc".to_string(), vec![Scope::ChatRead, Scope::ChatEdit])
+            .with_user("141981764", Some("twitchdev"));
+        events.token_changed(Some(&token), Some(token.scopes()), &upgraded);
+
+        assert_eq!(*seen.lock().unwrap(), 2);
+        assert!(matches!(receiver.try_recv().unwrap(), AuthEvent::TokenRefreshed { .. }));
+        match receiver.try_recv().unwrap() {
+            AuthEvent::ScopesChanged { user_id, previous, current } => {
+                assert_eq!(user_id.as_deref(), Some("141981764"));
+                assert_eq!(previous, vec![Scope::ChatRead]);
+                assert_eq!(current, vec![Scope::ChatRead, Scope::ChatEdit]);
+            }
+            event => panic!("unexpected event {:?}", event)
+        }
+        assert!(receiver.try_recv().is_err());
+    }
+}
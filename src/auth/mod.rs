@@ -1,6 +1,20 @@
 mod access_token;
+mod authorization_code;
+mod events;
+mod oidc;
 mod provider;
+mod scope;
+mod secret;
+mod token_store;
+mod validator;
 
-pub use self::access_token::{AccessToken, AccessTokenData};
-pub use self::provider::{AuthProvider, ClientCredentialsAuthProvider, RefreshableAuthProvider, StaticAuthProvider};
+pub use self::access_token::{AccessToken, AccessTokenData, TokenType};
+pub use self::authorization_code::{AuthorizationCodeFlow, OidcClaim, OidcClaimsRequest};
+pub use self::events::{AuthEvent, AuthEventCallback, AuthEvents};
+pub use self::oidc::{IdTokenClaims, IdTokenError, IdTokenVerifier, UserInfo, OIDC_ISSUER};
+pub use self::provider::{AuthProvider, AuthProviderError, ClientCredentialsAuthProvider, ExtensionAuthProvider, InteractiveAuthProvider, MissingScopesError, MultiUserAuthProvider, RefreshableAuthProvider, StaticAuthProvider, UpgradeCallback};
+pub use self::scope::{Scope, ScopeRequirement};
+pub use self::secret::Secret;
+pub use self::token_store::TokenStore;
+pub use self::validator::{TokenValidationEvent, TokenValidator, VALIDATION_INTERVAL};
 pub(crate) use provider::poly;
\ No newline at end of file
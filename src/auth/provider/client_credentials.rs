@@ -1,70 +1,84 @@
-use crate::auth::{AccessToken, AuthProvider, RefreshableAuthProvider};
+use std::sync::{Arc, Mutex};
+use crate::auth::{AccessToken, AuthEvents, AuthProvider, RefreshableAuthProvider, Scope, ScopeRequirement, Secret, TokenType};
 use futures::future::BoxFuture;
 use crate::auth::provider::provider::AuthProviderError;
-use std::borrow::Borrow;
 use futures::FutureExt;
 use crate::util::Result;
 use crate::api::ApiClient;
 
+#[derive(Clone)]
 pub struct ClientCredentialsAuthProvider {
     client_id: String,
-    client_secret: String,
-    current_token: Option<AccessToken>,
+    client_secret: Secret,
+    current_token: Arc<Mutex<Option<AccessToken>>>,
+    events: AuthEvents,
 }
 
 impl ClientCredentialsAuthProvider {
-    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>) -> ClientCredentialsAuthProvider {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<Secret>) -> ClientCredentialsAuthProvider {
         ClientCredentialsAuthProvider {
             client_id: client_id.into(),
             client_secret: client_secret.into(),
-            current_token: None,
+            current_token: Arc::new(Mutex::new(None)),
+            events: AuthEvents::new(),
         }
     }
 }
 
 impl AuthProvider for ClientCredentialsAuthProvider {
-    fn client_id(&self) -> &str {
-        self.client_id.as_str()
+    fn client_id(&self) -> String {
+        self.client_id.clone()
     }
 
-    fn current_scopes(&self) -> &[String] {
-        &[]
+    fn current_scopes(&self) -> Vec<Scope> {
+        vec![]
     }
 
-    fn access_token(&'a mut self) -> BoxFuture<Result<AccessToken>> {
-        async move {
-            match self.current_token.borrow() {
-                Some(token) => {
-                    if token.is_expired() {
-                        return self.refresh().await;
-                    }
-                    Ok(token.clone())
-                }
-                None => self.refresh().await
-            }
-        }.boxed()
+    fn token_type(&self) -> TokenType {
+        TokenType::App
     }
 
-    fn access_token_with_scopes(&'a mut self, scopes: Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>> {
-        async move {
-            if scopes.len() > 0 {
-                return Err(Box::new(AuthProviderError::new("The client credentials flow does not support scopes")) as Box<dyn std::error::Error + Send + Sync>)
-            }
-            self.access_token().await
-        }.boxed()
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        let current_token = self.current_token.lock().unwrap().clone();
+        match current_token {
+            Some(token) if !token.is_expired() => async move { Ok(token) }.boxed(),
+            _ => self.refresh()
+        }
     }
 
-    fn set_access_token(&mut self, token: AccessToken) {
-        self.current_token = Some(token);
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+        if !scopes.is_empty() {
+            return async move {
+                Err(Box::new(AuthProviderError::new("The client credentials flow does not support scopes")) as Box<dyn std::error::Error + Send + Sync>)
+            }.boxed();
+        }
+        self.access_token()
+    }
+
+    fn set_access_token(&self, token: AccessToken) {
+        let previous = self.current_token.lock().unwrap().replace(token.clone());
+        self.events.token_changed(previous.as_ref(), previous.as_ref().map(AccessToken::scopes), &token);
+    }
+
+    fn events(&self) -> Option<AuthEvents> {
+        Some(self.events.clone())
     }
 }
 
 impl RefreshableAuthProvider for ClientCredentialsAuthProvider {
-    fn refresh(&'a mut self) -> BoxFuture<'a, Result<AccessToken>> {
+    fn refresh(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        let provider = self.clone();
         async move {
-            let token = ApiClient::get_app_access_token(self.client_id.clone(), self.client_secret.clone()).await?;
-            self.current_token = Some(token.clone());
-            Ok(token)
+            match ApiClient::get_app_access_token(provider.client_id.clone(), &provider.client_secret).await {
+                Ok(token) => {
+                    provider.set_access_token(token.clone());
+                    Ok(token)
+                }
+                Err(e) => {
+                    provider.events.refresh_failed(None, e.as_ref());
+                    Err(e)
+                }
+            }
         }.boxed()
     }
-}
\ No newline at end of file
+}
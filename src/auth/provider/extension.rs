@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use crate::auth::{AccessToken, AccessTokenData, AuthEvents, AuthProvider, Scope, ScopeRequirement, TokenType};
+use crate::auth::provider::provider::AuthProviderError;
+use crate::extensions::jwt::{ExtensionClaims, ExtensionJwt};
+use crate::util::Result;
+
+// tokens are reissued a bit before they actually expire so they don't run out in flight
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+pub struct ExtensionAuthProvider {
+    client_id: String,
+    owner_id: String,
+    jwt: Arc<ExtensionJwt>,
+    token_lifetime: Duration,
+    current_token: Arc<Mutex<Option<AccessToken>>>,
+    events: AuthEvents,
+}
+
+impl ExtensionAuthProvider {
+    pub fn new(client_id: impl Into<String>, owner_id: impl Into<String>, jwt: ExtensionJwt) -> Self {
+        Self {
+            client_id: client_id.into(),
+            owner_id: owner_id.into(),
+            jwt: Arc::new(jwt),
+            token_lifetime: Duration::from_secs(3 * 60),
+            current_token: Arc::new(Mutex::new(None)),
+            events: AuthEvents::new(),
+        }
+    }
+
+    pub fn with_token_lifetime(mut self, token_lifetime: Duration) -> Self {
+        self.token_lifetime = token_lifetime;
+        self
+    }
+
+    fn sign_token(&self) -> Result<AccessToken> {
+        let claims = ExtensionClaims::external(self.owner_id.as_str(), self.token_lifetime);
+        Ok(AccessToken::new(AccessTokenData::new(
+            self.jwt.sign(&claims)?,
+            None,
+            Some(self.token_lifetime.saturating_sub(EXPIRY_MARGIN).as_secs()),
+            vec![],
+        )))
+    }
+}
+
+impl AuthProvider for ExtensionAuthProvider {
+    fn client_id(&self) -> String {
+        self.client_id.clone()
+    }
+
+    fn current_scopes(&self) -> Vec<Scope> {
+        vec![]
+    }
+
+    fn token_type(&self) -> TokenType {
+        TokenType::Extension
+    }
+
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        // events are emitted after the lock is released, as their callbacks may call back into the provider
+        let signed = {
+            let mut current_token = self.current_token.lock().unwrap();
+            match &*current_token {
+                Some(token) if !token.is_expired() => {
+                    let token = token.clone();
+                    return async move { Ok(token) }.boxed();
+                }
+                _ => self.sign_token().map(|token| (current_token.replace(token.clone()), token))
+            }
+        };
+        let token = match signed {
+            Ok((previous, token)) => {
+                self.events.token_changed(previous.as_ref(), previous.as_ref().map(AccessToken::scopes), &token);
+                Ok(token)
+            }
+            Err(e) => {
+                self.events.refresh_failed(None, e.as_ref());
+                Err(e)
+            }
+        };
+        async move { token }.boxed()
+    }
+
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+        if !scopes.is_empty() {
+            return async move {
+                Err(Box::new(AuthProviderError::new("Extension JWTs do not support scopes")) as Box<dyn std::error::Error + Send + Sync>)
+            }.boxed();
+        }
+        self.access_token()
+    }
+
+    fn set_access_token(&self, token: AccessToken) {
+        let previous = self.current_token.lock().unwrap().replace(token.clone());
+        self.events.token_changed(previous.as_ref(), previous.as_ref().map(AccessToken::scopes), &token);
+    }
+
+    fn events(&self) -> Option<AuthEvents> {
+        Some(self.events.clone())
+    }
+}
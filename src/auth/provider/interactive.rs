@@ -0,0 +1,164 @@
+use std::sync::{Arc, Mutex};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use crate::api::ApiClient;
+use crate::auth::{AccessToken, AuthEvents, AuthProvider, Scope, ScopeRequirement, TokenType};
+use crate::auth::authorization_code::authorize_url;
+use crate::auth::provider::provider::MissingScopesError;
+use crate::util::Result;
+
+pub type UpgradeCallback = Arc<dyn Fn(MissingScopesError) -> BoxFuture<'static, Result<AccessToken>> + Send + Sync>;
+
+// Like the static provider, but when a call needs more scopes than the token has, it hands the
+// missing scopes and an authorize URL to the callback, which is expected to get the user through
+// the authorization and resolve with the new token. The call then continues with that token.
+#[derive(Clone)]
+pub struct InteractiveAuthProvider {
+    client_id: String,
+    redirect_uri: String,
+    access_token: Arc<Mutex<AccessToken>>,
+    on_upgrade: UpgradeCallback,
+    // makes concurrent calls wait for a running upgrade instead of starting their own
+    upgrade_lock: Arc<tokio::sync::Mutex<()>>,
+    events: AuthEvents,
+}
+
+impl InteractiveAuthProvider {
+    pub fn new<F>(client_id: impl Into<String>, redirect_uri: impl Into<String>, access_token: AccessToken, on_upgrade: F) -> Self
+        where F: Fn(MissingScopesError) -> BoxFuture<'static, Result<AccessToken>> + Send + Sync + 'static {
+        Self {
+            client_id: client_id.into(),
+            redirect_uri: redirect_uri.into(),
+            access_token: Arc::new(Mutex::new(access_token)),
+            on_upgrade: Arc::new(on_upgrade),
+            upgrade_lock: Arc::new(tokio::sync::Mutex::new(())),
+            events: AuthEvents::new(),
+        }
+    }
+
+    // tokens without identity have not been validated yet, so their scope list can't be trusted
+    async fn validated(client_id: &str, token: AccessToken) -> Result<AccessToken> {
+        if token.user_id().is_some() {
+            return Ok(token);
+        }
+        let token_info = ApiClient::get_token_info_for_access_token(client_id, token.access_token()).await?;
+        Ok(token.with_token_info(&token_info))
+    }
+}
+
+impl AuthProvider for InteractiveAuthProvider {
+    fn client_id(&self) -> String {
+        self.client_id.clone()
+    }
+
+    fn current_scopes(&self) -> Vec<Scope> {
+        self.access_token.lock().unwrap().scopes().to_vec()
+    }
+
+    fn token_type(&self) -> TokenType {
+        TokenType::User
+    }
+
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        let token = self.access_token.lock().unwrap().clone();
+        async move { Ok(token) }.boxed()
+    }
+
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+        let provider = self.clone();
+        async move {
+            let _upgrade_guard = provider.upgrade_lock.lock().await;
+            let token = provider.access_token.lock().unwrap().clone();
+            let token = Self::validated(provider.client_id.as_str(), token).await?;
+            provider.set_access_token(token.clone());
+            if scopes.is_satisfied_by(token.scopes()) {
+                return Ok(token);
+            }
+
+            let current_scopes = token.scopes().to_vec();
+            let missing_scopes = scopes.missing_from(&current_scopes);
+            let requested_scopes: Vec<Scope> = current_scopes.iter().chain(missing_scopes.iter()).cloned().collect();
+            let authorize_url = authorize_url(provider.client_id.as_str(), provider.redirect_uri.as_str(), &requested_scopes, &[]);
+            let new_token = (provider.on_upgrade)(MissingScopesError::new(missing_scopes, current_scopes, Some(authorize_url))).await?;
+
+            let token = Self::validated(provider.client_id.as_str(), new_token).await?;
+            provider.set_access_token(token.clone());
+            if !scopes.is_satisfied_by(token.scopes()) {
+                let current_scopes = token.scopes().to_vec();
+                return Err(Box::new(MissingScopesError::new(scopes.missing_from(&current_scopes), current_scopes, None)) as Box<dyn std::error::Error + Send + Sync>);
+            }
+            Ok(token)
+        }.boxed()
+    }
+
+    fn set_access_token(&self, token: AccessToken) {
+        let previous = std::mem::replace(&mut *self.access_token.lock().unwrap(), token.clone());
+        // the scopes of a token are only known once it has been validated
+        let previous_scopes = previous.user_id().map(|_| previous.scopes());
+        self.events.token_changed(Some(&previous), previous_scopes, &token);
+    }
+
+    fn events(&self) -> Option<AuthEvents> {
+        Some(self.events.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use futures::FutureExt;
+    use crate::auth::{AccessToken, AuthEvent, AuthProvider, Scope};
+    use super::InteractiveAuthProvider;
+
+    // tokens that know their user count as validated, so no request goes out
+    fn validated_token(access_token: &str, scopes: Vec<Scope>) -> AccessToken {
+        AccessToken::with_access_token_and_scopes(access_token, scopes).with_user("141981764", Some("twitchdev"))
+    }
+
+    #[tokio::test]
+    async fn upgrades_missing_scopes_once() {
+        let upgrades = Arc::new(AtomicUsize::new(0));
+        let counter = upgrades.clone();
+        let provider = InteractiveAuthProvider::new(
+            "client-id",
+            "http://localhost/callback",
+            validated_token("old", vec![Scope::ChatRead]),
+            move |error| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(error.missing_scopes(), &[Scope::ChatEdit]);
+                assert!(error.authorize_url().unwrap().as_str().contains("chat%3Aedit"));
+                async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok(validated_token("new", vec![Scope::ChatRead, Scope::ChatEdit]))
+                }.boxed()
+            },
+        );
+        let mut events = provider.events().unwrap().subscribe();
+
+        let (first, second) = tokio::join!(
+            provider.access_token_with_scopes(Scope::ChatEdit.into()),
+            provider.access_token_with_scopes(Scope::ChatEdit.into()),
+        );
+        assert_eq!(first.unwrap().access_token().expose(), "new");
+        assert_eq!(second.unwrap().access_token().expose(), "new");
+        assert_eq!(upgrades.load(Ordering::SeqCst), 1);
+
+        let token = provider.access_token_with_scopes(Scope::ChatRead.into()).await.unwrap();
+        assert_eq!(token.access_token().expose(), "new");
+        assert_eq!(upgrades.load(Ordering::SeqCst), 1);
+
+        assert!(matches!(events.try_recv().unwrap(), AuthEvent::TokenRefreshed { .. }));
+        match events.try_recv().unwrap() {
+            AuthEvent::ScopesChanged { user_id, previous, current } => {
+                assert_eq!(user_id.as_deref(), Some("141981764"));
+                assert_eq!(previous, vec![Scope::ChatRead]);
+                assert_eq!(current, vec![Scope::ChatRead, Scope::ChatEdit]);
+            }
+            event => panic!("unexpected event {:?}", event)
+        }
+        assert!(events.try_recv().is_err());
+    }
+}
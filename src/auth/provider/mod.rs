@@ -1,8 +1,14 @@
 mod provider;
 mod client_credentials;
+mod extension;
+mod interactive;
+mod multi_user;
 pub(crate) mod poly;
 mod stat;
 
-pub use self::provider::{AuthProvider, RefreshableAuthProvider};
+pub use self::provider::{AuthProvider, AuthProviderError, MissingScopesError, RefreshableAuthProvider};
 pub use self::client_credentials::ClientCredentialsAuthProvider;
+pub use self::extension::ExtensionAuthProvider;
+pub use self::interactive::{InteractiveAuthProvider, UpgradeCallback};
+pub use self::multi_user::MultiUserAuthProvider;
 pub use self::stat::StaticAuthProvider;
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::api::ApiClient;
+use crate::auth::{AccessToken, AuthEvents, ScopeRequirement, Secret, TokenStore};
+use crate::auth::provider::provider::{AuthProviderError, MissingScopesError};
+use crate::util::Result;
+
+pub struct MultiUserAuthProvider {
+    client_id: String,
+    client_secret: Secret,
+    tokens: Mutex<HashMap<String, AccessToken>>,
+    store: Option<Arc<dyn TokenStore + Send + Sync>>,
+    events: AuthEvents,
+}
+
+impl MultiUserAuthProvider {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<Secret>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            tokens: Mutex::new(HashMap::new()),
+            store: None,
+            events: AuthEvents::new(),
+        }
+    }
+
+    pub fn with_token_store(mut self, store: impl TokenStore + Send + Sync + 'static) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    pub fn client_id(&self) -> &str {
+        self.client_id.as_str()
+    }
+
+    // shared by all users; every event names the user it is about
+    pub fn events(&self) -> AuthEvents {
+        self.events.clone()
+    }
+
+    // only the users whose tokens are currently loaded, not everything in the token store
+    pub fn user_ids(&self) -> Vec<String> {
+        self.tokens.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub async fn add_user(&self, user_id: impl Into<String>, token: AccessToken) -> Result<()> {
+        let user_id = user_id.into();
+        if let Some(store) = &self.store {
+            store.save_token(user_id.as_str(), &token).await?;
+        }
+        self.tokens.lock().unwrap().insert(user_id, token);
+        Ok(())
+    }
+
+    pub async fn remove_user(&self, user_id: &str) -> Result<Option<AccessToken>> {
+        if let Some(store) = &self.store {
+            store.remove_token(user_id).await?;
+        }
+        Ok(self.tokens.lock().unwrap().remove(user_id))
+    }
+
+    pub async fn access_token_for_user(&self, user_id: &str, scopes: &ScopeRequirement) -> Result<AccessToken> {
+        let mut token = self.token_for_user(user_id).await?;
+        if token.is_expired() {
+            token = self.refresh_token_for_user(user_id, &token).await?;
+        }
+        self.tokens.lock().unwrap().insert(user_id.to_string(), token.clone());
+
+        if !scopes.is_satisfied_by(token.scopes()) {
+            return Err(Box::new(MissingScopesError::new(scopes.missing_from(token.scopes()), token.scopes().to_vec(), None)));
+        }
+
+        Ok(token)
+    }
+
+    pub async fn refresh_user(&self, user_id: &str) -> Result<AccessToken> {
+        let token = self.token_for_user(user_id).await?;
+        let token = self.refresh_token_for_user(user_id, &token).await?;
+        self.tokens.lock().unwrap().insert(user_id.to_string(), token.clone());
+        Ok(token)
+    }
+
+    async fn token_for_user(&self, user_id: &str) -> Result<AccessToken> {
+        let token = self.tokens.lock().unwrap().get(user_id).cloned();
+        match token {
+            Some(token) => Ok(token),
+            None => self.load_token(user_id).await
+        }
+    }
+
+    async fn load_token(&self, user_id: &str) -> Result<AccessToken> {
+        let token = match &self.store {
+            Some(store) => store.load_token(user_id).await?,
+            None => None
+        };
+        token.ok_or_else(|| Box::new(AuthProviderError::new(format!("There is no token for user {}", user_id))).into())
+    }
+
+    async fn refresh_token_for_user(&self, user_id: &str, token: &AccessToken) -> Result<AccessToken> {
+        let refresh_token = match token.refresh_token() {
+            Some(refresh_token) => refresh_token,
+            None => return Err(Box::new(AuthProviderError::new(format!(
+                "The token for user {} is expired and can not be refreshed",
+                user_id))))
+        };
+        let new_token = match ApiClient::refresh_access_token(self.client_id.as_str(), &self.client_secret, refresh_token).await {
+            Ok(new_token) => new_token,
+            Err(e) => {
+                self.events.refresh_failed(Some(user_id), e.as_ref());
+                return Err(e);
+            }
+        };
+        // the refreshed token belongs to the same user, which the events and the store rely on
+        let new_token = new_token.with_user(user_id, token.login());
+        if let Some(store) = &self.store {
+            store.save_token(user_id, &new_token).await?;
+        }
+        self.events.token_changed(Some(token), Some(token.scopes()), &new_token);
+        Ok(new_token)
+    }
+}
@@ -1,5 +1,5 @@
 use std::ptr::NonNull;
-use crate::auth::{AuthProvider, AccessToken};
+use crate::auth::{AuthEvents, AuthProvider, AccessToken, Scope, ScopeRequirement, TokenType};
 use crate::util::Result;
 use futures::future::BoxFuture;
 use crate::auth::provider::poly::CAuthProvider;
@@ -9,7 +9,7 @@ pub struct OwnedAuthProvider(NonNull<CAuthProvider>);
 
 impl OwnedAuthProvider {
     pub fn new<A>(provider: A) -> Self
-        where A: AuthProvider + Send + Sync + 'static {
+        where A: AuthProvider + 'static {
         unsafe {
             let provider_ptr = CAuthProvider::for_auth_provider(provider);
             assert!(!provider_ptr.is_null());
@@ -27,49 +27,48 @@ impl OwnedAuthProvider {
         std::mem::forget(self);
         ptr
     }
+
+    fn vtable(&self) -> &CAuthProvider {
+        unsafe { self.0.as_ref() }
+    }
 }
 
 impl AuthProvider for OwnedAuthProvider {
-    fn client_id(&self) -> &str {
-        unsafe {
-            let ptr = self.0.as_ptr();
-            let CAuthProvider { client_id, .. } = *ptr;
-            (client_id)(ptr)
-        }
+    fn client_id(&self) -> String {
+        unsafe { (self.vtable().client_id)(self.0.as_ptr()) }
     }
 
-    fn current_scopes(&self) -> &[String] {
-        unsafe {
-            let ptr = self.0.as_ptr();
-            let CAuthProvider { current_scopes, .. } = *ptr;
-            (current_scopes)(ptr)
-        }
+    fn current_scopes(&self) -> Vec<Scope> {
+        unsafe { (self.vtable().current_scopes)(self.0.as_ptr()) }
     }
 
-    fn access_token(&mut self) -> BoxFuture<Result<AccessToken>> {
-        unsafe {
-            let ptr = self.0.as_ptr();
-            let CAuthProvider { access_token, .. } = *ptr;
-            (access_token)(ptr)
-        }
+    fn token_type(&self) -> TokenType {
+        unsafe { (self.vtable().token_type)(self.0.as_ptr()) }
     }
 
-    fn access_token_with_scopes(&'a mut self, scopes: Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>> {
-        unsafe {
-            let ptr = self.0.as_ptr();
-            let CAuthProvider { access_token_with_scopes, .. } = *ptr;
-            (access_token_with_scopes)(ptr, scopes)
-        }
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        unsafe { (self.vtable().access_token)(self.0.as_ptr()) }
     }
 
-    fn set_access_token(&mut self, token: AccessToken) {
-        unsafe {
-            let ptr = self.0.as_ptr();
-            let CAuthProvider { set_access_token, .. } = *ptr;
-            (set_access_token)(ptr, token)
-        }
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+        unsafe { (self.vtable().access_token_with_scopes)(self.0.as_ptr(), scopes) }
+    }
+
+    fn set_access_token(&self, token: AccessToken) {
+        unsafe { (self.vtable().set_access_token)(self.0.as_ptr(), token) }
+    }
+
+    fn events(&self) -> Option<AuthEvents> {
+        unsafe { (self.vtable().events)(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for OwnedAuthProvider {
+    fn drop(&mut self) {
+        unsafe { (self.vtable().drop)(self.0.as_ptr()) }
     }
 }
 
+// the wrapped provider is required to be Send + Sync by the AuthProvider trait itself
 unsafe impl Send for OwnedAuthProvider {}
-unsafe impl Sync for OwnedAuthProvider {}
\ No newline at end of file
+unsafe impl Sync for OwnedAuthProvider {}
@@ -2,16 +2,20 @@ use std::any::TypeId;
 
 use crate::util::Result;
 use futures::future::BoxFuture;
-use crate::auth::{AccessToken, AuthProvider};
+use crate::auth::{AccessToken, AuthEvents, AuthProvider, Scope, ScopeRequirement, TokenType};
 
+// every entry only borrows the provider for the duration of the call and hands back owned data
 #[repr(C)]
 pub struct CAuthProvider {
     pub(crate) type_id: TypeId,
-    pub(crate) access_token: unsafe fn(*mut CAuthProvider) -> BoxFuture<'static, Result<AccessToken>>,
-    pub(crate) access_token_with_scopes: for<'a> unsafe fn(*mut CAuthProvider, Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>>,
-    pub(crate) client_id: unsafe fn(*mut CAuthProvider) -> &'static str,
-    pub(crate) current_scopes: unsafe fn(*mut CAuthProvider) -> &'static [String],
-    pub(crate) set_access_token: unsafe fn(*mut CAuthProvider, AccessToken),
+    pub(crate) access_token: unsafe fn(*const CAuthProvider) -> BoxFuture<'static, Result<AccessToken>>,
+    pub(crate) access_token_with_scopes: unsafe fn(*const CAuthProvider, ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>>,
+    pub(crate) client_id: unsafe fn(*const CAuthProvider) -> String,
+    pub(crate) current_scopes: unsafe fn(*const CAuthProvider) -> Vec<Scope>,
+    pub(crate) token_type: unsafe fn(*const CAuthProvider) -> TokenType,
+    pub(crate) set_access_token: unsafe fn(*const CAuthProvider, AccessToken),
+    pub(crate) events: unsafe fn(*const CAuthProvider) -> Option<AuthEvents>,
+    pub(crate) drop: unsafe fn(*mut CAuthProvider),
 }
 
 #[repr(C)]
@@ -22,7 +26,7 @@ struct CAuthProviderWrapper<A> {
 
 impl CAuthProvider {
     pub fn for_auth_provider<A>(provider: A) -> *mut CAuthProvider
-        where A: AuthProvider + Send + Sync + 'static, {
+        where A: AuthProvider + 'static, {
         let wrap = CAuthProviderWrapper {
             vt: CAuthProvider::vtable::<A>(),
             provider,
@@ -36,29 +40,41 @@ impl CAuthProvider {
     fn vtable<A: AuthProvider + 'static>() -> CAuthProvider {
         let type_id = TypeId::of::<A>();
 
-        unsafe fn client_id<A: AuthProvider + 'static>(provider: *mut CAuthProvider) -> &'static str {
-            let wrap = &mut *(provider as *mut CAuthProviderWrapper<A>);
-            wrap.provider.client_id()
+        unsafe fn provider<'a, A: AuthProvider + 'static>(provider: *const CAuthProvider) -> &'a A {
+            let wrap = &*(provider as *const CAuthProviderWrapper<A>);
+            &wrap.provider
         }
 
-        unsafe fn access_token<A: AuthProvider + 'static>(provider: *mut CAuthProvider) -> BoxFuture<'static, Result<AccessToken>> {
-            let wrap = &mut *(provider as *mut CAuthProviderWrapper<A>);
-            wrap.provider.access_token()
+        unsafe fn client_id<A: AuthProvider + 'static>(ptr: *const CAuthProvider) -> String {
+            provider::<A>(ptr).client_id()
         }
 
-        unsafe fn access_token_with_scopes<'a, A: AuthProvider + 'static>(provider: *mut CAuthProvider, scopes: Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>> {
-            let wrap = &mut *(provider as *mut CAuthProviderWrapper<A>);
-            wrap.provider.access_token_with_scopes(scopes)
+        unsafe fn access_token<A: AuthProvider + 'static>(ptr: *const CAuthProvider) -> BoxFuture<'static, Result<AccessToken>> {
+            provider::<A>(ptr).access_token()
         }
 
-        unsafe fn current_scopes<A: AuthProvider + 'static>(provider: *mut CAuthProvider) -> &'static [String] {
-            let wrap = &mut *(provider as *mut CAuthProviderWrapper<A>);
-            wrap.provider.current_scopes()
+        unsafe fn access_token_with_scopes<A: AuthProvider + 'static>(ptr: *const CAuthProvider, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+            provider::<A>(ptr).access_token_with_scopes(scopes)
         }
 
-        unsafe fn set_access_token<A: AuthProvider + 'static>(provider: *mut CAuthProvider, access_token: AccessToken) {
-            let wrap = &mut *(provider as *mut CAuthProviderWrapper<A>);
-            wrap.provider.set_access_token(access_token);
+        unsafe fn current_scopes<A: AuthProvider + 'static>(ptr: *const CAuthProvider) -> Vec<Scope> {
+            provider::<A>(ptr).current_scopes()
+        }
+
+        unsafe fn token_type<A: AuthProvider + 'static>(ptr: *const CAuthProvider) -> TokenType {
+            provider::<A>(ptr).token_type()
+        }
+
+        unsafe fn set_access_token<A: AuthProvider + 'static>(ptr: *const CAuthProvider, access_token: AccessToken) {
+            provider::<A>(ptr).set_access_token(access_token);
+        }
+
+        unsafe fn events<A: AuthProvider + 'static>(ptr: *const CAuthProvider) -> Option<AuthEvents> {
+            provider::<A>(ptr).events()
+        }
+
+        unsafe fn drop<A: AuthProvider + 'static>(ptr: *mut CAuthProvider) {
+            std::mem::drop(Box::from_raw(ptr as *mut CAuthProviderWrapper<A>));
         }
 
         CAuthProvider {
@@ -67,7 +83,10 @@ impl CAuthProvider {
             access_token: access_token::<A>,
             access_token_with_scopes: access_token_with_scopes::<A>,
             current_scopes: current_scopes::<A>,
-            set_access_token: set_access_token::<A>
+            token_type: token_type::<A>,
+            set_access_token: set_access_token::<A>,
+            events: events::<A>,
+            drop: drop::<A>,
         }
     }
-}
\ No newline at end of file
+}
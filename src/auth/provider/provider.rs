@@ -1,23 +1,32 @@
 use futures::future::{BoxFuture};
+use url::Url;
 use std::{
     borrow::{Borrow, Cow},
     error::Error,
     fmt::Display,
 };
 
-use crate::auth::AccessToken;
+use crate::auth::{AccessToken, AuthEvents, Scope, ScopeRequirement, TokenType};
 use crate::util::Result;
 
-pub trait AuthProvider {
-    fn client_id(&self) -> &str;
-    fn current_scopes(&self) -> &[String];
-    fn access_token(&mut self) -> BoxFuture<Result<AccessToken>>;
-    fn access_token_with_scopes(&'a mut self, scopes: Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>>;
-    fn set_access_token(&mut self, token: AccessToken);
+// Providers are shared between the API client, background tasks and the FFI layer, so everything
+// here works through `&self` and hands out owned data; the futures must not borrow the provider.
+pub trait AuthProvider: Send + Sync {
+    fn client_id(&self) -> String;
+    fn current_scopes(&self) -> Vec<Scope>;
+    fn token_type(&self) -> TokenType;
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>>;
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>>;
+    fn set_access_token(&self, token: AccessToken);
+
+    // providers that never change their token have nothing to report
+    fn events(&self) -> Option<AuthEvents> {
+        None
+    }
 }
 
 pub trait RefreshableAuthProvider: AuthProvider {
-    fn refresh(&'a mut self) -> BoxFuture<'a, Result<AccessToken>>;
+    fn refresh(&self) -> BoxFuture<'static, Result<AccessToken>>;
 }
 
 #[derive(Debug)]
@@ -41,6 +50,52 @@ impl<'a> Error for AuthProviderError<'a> {
 
 impl<'a> Display for AuthProviderError<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        self.to_string().fmt(f)
+        f.write_str(self.description.borrow())
+    }
+}
+#[derive(Debug)]
+pub struct MissingScopesError {
+    missing_scopes: Vec<Scope>,
+    current_scopes: Vec<Scope>,
+    authorize_url: Option<Url>,
+}
+
+impl MissingScopesError {
+    pub fn new(missing_scopes: Vec<Scope>, current_scopes: Vec<Scope>, authorize_url: Option<Url>) -> Self {
+        Self {
+            missing_scopes,
+            current_scopes,
+            authorize_url,
+        }
+    }
+
+    pub fn missing_scopes(&self) -> &[Scope] {
+        self.missing_scopes.as_slice()
+    }
+
+    pub fn current_scopes(&self) -> &[Scope] {
+        self.current_scopes.as_slice()
+    }
+
+    pub fn requested_scopes(&self) -> Vec<Scope> {
+        self.current_scopes.iter().chain(self.missing_scopes.iter()).cloned().collect()
+    }
+
+    // only available when the provider knows where to redirect the user to
+    pub fn authorize_url(&self) -> Option<&Url> {
+        self.authorize_url.as_ref()
+    }
+}
+
+impl Error for MissingScopesError {}
+
+impl Display for MissingScopesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let join = |scopes: &[Scope]| scopes.iter().map(Scope::as_str).collect::<Vec<_>>().join(", ");
+        write!(f, "This token is missing the scopes {} (it has: {})", join(&self.missing_scopes), join(&self.current_scopes))?;
+        if let Some(url) = &self.authorize_url {
+            write!(f, "; reauthorize at {}", url)?;
+        }
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,78 +1,181 @@
-use crate::auth::{AuthProvider, AccessToken};
+use std::sync::{Arc, Mutex};
+use crate::auth::{AuthEvents, AuthProvider, AccessToken, Scope, ScopeRequirement, Secret, TokenType};
 use crate::util::Result;
 use futures::future::BoxFuture;
 use crate::api::ApiClient;
-use crate::auth::provider::provider::AuthProviderError;
+use crate::auth::authorization_code::authorize_url;
+use crate::auth::provider::provider::MissingScopesError;
 use futures::FutureExt;
 
+struct StaticAuthState {
+    access_token: AccessToken,
+    scopes: Option<Vec<Scope>>,
+}
+
 #[derive(Clone)]
 pub struct StaticAuthProvider {
     client_id: String,
-    access_token: AccessToken,
-    scopes: Option<Vec<String>>,
+    token_type: TokenType,
+    redirect_uri: Option<String>,
+    state: Arc<Mutex<StaticAuthState>>,
+    events: AuthEvents,
 }
 
 impl StaticAuthProvider {
-    pub fn new(client_id: String, access_token: String) -> Self {
+    pub fn new(client_id: String, access_token: impl Into<Secret>) -> Self {
         Self {
             client_id,
-            access_token: AccessToken::with_access_token_and_scopes(
-                access_token,
-                vec![],
-            ),
-            scopes: None,
+            token_type: TokenType::User,
+            redirect_uri: None,
+            state: Arc::new(Mutex::new(StaticAuthState {
+                access_token: AccessToken::with_access_token_and_scopes(
+                    access_token,
+                    vec![],
+                ),
+                scopes: None,
+            })),
+            events: AuthEvents::new(),
         }
     }
 
-    pub fn with_scopes(client_id: String, access_token: String, scopes: Vec<String>) -> Self {
+    pub fn with_scopes(client_id: String, access_token: impl Into<Secret>, scopes: Vec<Scope>) -> Self {
         Self {
             client_id,
-            access_token: AccessToken::with_access_token_and_scopes(
-                access_token,
-                scopes.clone(),
-            ),
-            scopes: Some(scopes),
+            token_type: TokenType::User,
+            redirect_uri: None,
+            state: Arc::new(Mutex::new(StaticAuthState {
+                access_token: AccessToken::with_access_token_and_scopes(
+                    access_token,
+                    scopes.clone(),
+                ),
+                scopes: Some(scopes),
+            })),
+            events: AuthEvents::new(),
         }
     }
+
+    pub async fn validated(client_id: String, access_token: impl Into<Secret>) -> Result<Self> {
+        let access_token = access_token.into();
+        let token_info = ApiClient::get_token_info_for_access_token(client_id.as_str(), &access_token).await?;
+        let token_type = match token_info.user_id() {
+            Some(_) => TokenType::User,
+            None => TokenType::App
+        };
+        Ok(Self {
+            client_id,
+            token_type,
+            redirect_uri: None,
+            state: Arc::new(Mutex::new(StaticAuthState {
+                access_token: AccessToken::with_access_token(access_token).with_token_info(&token_info),
+                scopes: Some(token_info.scopes().to_vec()),
+            })),
+            events: AuthEvents::new(),
+        })
+    }
+
+    pub fn with_token_type(mut self, token_type: TokenType) -> Self {
+        self.token_type = token_type;
+        self
+    }
+
+    // enables a ready-made authorize URL when scopes are missing
+    pub fn with_redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+
+    fn missing_scopes_error(&self, scopes: &ScopeRequirement, current_scopes: Vec<Scope>) -> MissingScopesError {
+        let missing_scopes = scopes.missing_from(&current_scopes);
+        let authorize_url = self.redirect_uri.as_ref().map(|redirect_uri| {
+            let requested_scopes: Vec<Scope> = current_scopes.iter().chain(missing_scopes.iter()).cloned().collect();
+            authorize_url(self.client_id.as_str(), redirect_uri.as_str(), &requested_scopes, &[])
+        });
+        MissingScopesError::new(missing_scopes, current_scopes, authorize_url)
+    }
 }
 
 impl AuthProvider for StaticAuthProvider {
-    fn client_id(&self) -> &str {
-        self.client_id.as_str()
+    fn client_id(&self) -> String {
+        self.client_id.clone()
     }
 
-    fn current_scopes(&self) -> &[String] {
-        self.scopes.as_ref().map_or(&[], Vec::as_slice)
+    fn current_scopes(&self) -> Vec<Scope> {
+        self.state.lock().unwrap().scopes.clone().unwrap_or_default()
     }
 
-    fn access_token(&'a mut self) -> BoxFuture<'a, Result<AccessToken>> {
-        async move { Ok(self.access_token.clone()) }.boxed()
+    fn token_type(&self) -> TokenType {
+        self.token_type
     }
 
-    fn access_token_with_scopes(&'a mut self, scopes: Vec<&'a str>) -> BoxFuture<'a, Result<AccessToken>> {
+    fn access_token(&self) -> BoxFuture<'static, Result<AccessToken>> {
+        let token = self.state.lock().unwrap().access_token.clone();
+        async move { Ok(token) }.boxed()
+    }
+
+    fn access_token_with_scopes(&self, scopes: ScopeRequirement) -> BoxFuture<'static, Result<AccessToken>> {
+        let provider = self.clone();
         async move {
-            if !scopes.is_empty() {
-                if self.scopes.is_none() {
+            let (access_token, current_scopes) = {
+                let state = provider.state.lock().unwrap();
+                (state.access_token.clone(), state.scopes.clone())
+            };
+            if scopes.is_empty() {
+                return Ok(access_token);
+            }
+
+            let current_scopes = match current_scopes {
+                Some(current_scopes) => current_scopes,
+                None => {
                     let token_info = ApiClient::get_token_info_for_access_token(
-                        self.client_id.clone(),
-                        self.access_token.access_token(),
+                        provider.client_id.clone(),
+                        access_token.access_token(),
                     ).await?;
-                    self.scopes = Some(token_info.scopes().to_owned())
-                }
-                let current_scopes = self.scopes.as_ref().unwrap();
-                if scopes.iter().any(|scope| !current_scopes.iter().any(|inner_scope| inner_scope == scope)) {
-                    return Err(Box::new(AuthProviderError::new(format!(
-                        "This token does not have the requested scopes ({}) and can not be upgraded",
-                        scopes.join(", ")))) as Box<dyn std::error::Error + Send + Sync>
-                    );
+                    let current_scopes = token_info.scopes().to_vec();
+                    provider.state.lock().unwrap().scopes = Some(current_scopes.clone());
+                    current_scopes
                 }
+            };
+            if !scopes.is_satisfied_by(&current_scopes) {
+                return Err(Box::new(provider.missing_scopes_error(&scopes, current_scopes)) as Box<dyn std::error::Error + Send + Sync>);
             }
 
-            Ok(self.access_token.clone())
+            Ok(access_token)
         }.boxed()
     }
 
-    fn set_access_token(&mut self, token: AccessToken) {
-        self.access_token = token;
+    fn set_access_token(&self, token: AccessToken) {
+        let (previous, previous_scopes) = {
+            let mut state = self.state.lock().unwrap();
+            // a validated token (e.g. from the validator) brings its scopes along; for any other token
+            // they are unknown until it is validated, rather than known to be empty
+            let scopes = match token.user_id().is_some() || !token.scopes().is_empty() {
+                true => Some(token.scopes().to_vec()),
+                false => None
+            };
+            let previous_scopes = std::mem::replace(&mut state.scopes, scopes);
+            (std::mem::replace(&mut state.access_token, token.clone()), previous_scopes)
+        };
+        self.events.token_changed(Some(&previous), previous_scopes.as_deref(), &token);
+    }
+
+    fn events(&self) -> Option<AuthEvents> {
+        Some(self.events.clone())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::{AccessToken, AuthProvider, Scope};
+    use super::StaticAuthProvider;
+
+    #[test]
+    fn forgets_scopes_when_given_an_unvalidated_token() {
+        let provider = StaticAuthProvider::with_scopes("client-id".to_string(), "token", vec![Scope::UserEdit]);
+        provider.set_access_token(AccessToken::with_access_token_and_scopes("other", vec![Scope::ChatRead]));
+        assert_eq!(provider.state.lock().unwrap().scopes, Some(vec![Scope::ChatRead]));
+
+        provider.set_access_token(AccessToken::with_access_token("unvalidated"));
+        assert_eq!(provider.state.lock().unwrap().scopes, None);
+        assert!(provider.current_scopes().is_empty());
+    }
+}
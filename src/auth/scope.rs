@@ -0,0 +1,263 @@
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    str::FromStr,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! scopes {
+    ($($variant:ident => $name:literal,)+) => {
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum Scope {
+            $($variant,)+
+            Unknown(String),
+        }
+
+        impl Scope {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Scope::$variant => $name,)+
+                    Scope::Unknown(name) => name.as_str(),
+                }
+            }
+        }
+
+        impl From<&str> for Scope {
+            fn from(name: &str) -> Self {
+                match name {
+                    $($name => Scope::$variant,)+
+                    _ => Scope::Unknown(name.to_string()),
+                }
+            }
+        }
+    };
+}
+
+scopes! {
+    AnalyticsReadExtensions => "analytics:read:extensions",
+    AnalyticsReadGames => "analytics:read:games",
+    BitsRead => "bits:read",
+    ChannelBot => "channel:bot",
+    ChannelEditCommercial => "channel:edit:commercial",
+    ChannelManageAds => "channel:manage:ads",
+    ChannelManageBroadcast => "channel:manage:broadcast",
+    ChannelManageExtensions => "channel:manage:extensions",
+    ChannelManageGuestStar => "channel:manage:guest_star",
+    ChannelManageModerators => "channel:manage:moderators",
+    ChannelManagePolls => "channel:manage:polls",
+    ChannelManagePredictions => "channel:manage:predictions",
+    ChannelManageRaids => "channel:manage:raids",
+    ChannelManageRedemptions => "channel:manage:redemptions",
+    ChannelManageSchedule => "channel:manage:schedule",
+    ChannelManageVideos => "channel:manage:videos",
+    ChannelManageVips => "channel:manage:vips",
+    ChannelModerate => "channel:moderate",
+    ChannelReadAds => "channel:read:ads",
+    ChannelReadCharity => "channel:read:charity",
+    ChannelReadEditors => "channel:read:editors",
+    ChannelReadGoals => "channel:read:goals",
+    ChannelReadGuestStar => "channel:read:guest_star",
+    ChannelReadHypeTrain => "channel:read:hype_train",
+    ChannelReadPolls => "channel:read:polls",
+    ChannelReadPredictions => "channel:read:predictions",
+    ChannelReadRedemptions => "channel:read:redemptions",
+    ChannelReadStreamKey => "channel:read:stream_key",
+    ChannelReadSubscriptions => "channel:read:subscriptions",
+    ChannelReadVips => "channel:read:vips",
+    ChatEdit => "chat:edit",
+    ChatRead => "chat:read",
+    ClipsEdit => "clips:edit",
+    ModerationRead => "moderation:read",
+    ModeratorManageAnnouncements => "moderator:manage:announcements",
+    ModeratorManageAutomod => "moderator:manage:automod",
+    ModeratorManageAutomodSettings => "moderator:manage:automod_settings",
+    ModeratorManageBannedUsers => "moderator:manage:banned_users",
+    ModeratorManageBlockedTerms => "moderator:manage:blocked_terms",
+    ModeratorManageChatMessages => "moderator:manage:chat_messages",
+    ModeratorManageChatSettings => "moderator:manage:chat_settings",
+    ModeratorManageGuestStar => "moderator:manage:guest_star",
+    ModeratorManageShieldMode => "moderator:manage:shield_mode",
+    ModeratorManageShoutouts => "moderator:manage:shoutouts",
+    ModeratorManageUnbanRequests => "moderator:manage:unban_requests",
+    ModeratorManageWarnings => "moderator:manage:warnings",
+    ModeratorReadAutomodSettings => "moderator:read:automod_settings",
+    ModeratorReadBannedUsers => "moderator:read:banned_users",
+    ModeratorReadBlockedTerms => "moderator:read:blocked_terms",
+    ModeratorReadChatMessages => "moderator:read:chat_messages",
+    ModeratorReadChatSettings => "moderator:read:chat_settings",
+    ModeratorReadChatters => "moderator:read:chatters",
+    ModeratorReadFollowers => "moderator:read:followers",
+    ModeratorReadGuestStar => "moderator:read:guest_star",
+    ModeratorReadModerators => "moderator:read:moderators",
+    ModeratorReadShieldMode => "moderator:read:shield_mode",
+    ModeratorReadShoutouts => "moderator:read:shoutouts",
+    ModeratorReadSuspiciousUsers => "moderator:read:suspicious_users",
+    ModeratorReadUnbanRequests => "moderator:read:unban_requests",
+    ModeratorReadVips => "moderator:read:vips",
+    ModeratorReadWarnings => "moderator:read:warnings",
+    OpenId => "openid",
+    UserBot => "user:bot",
+    UserEdit => "user:edit",
+    UserEditBroadcast => "user:edit:broadcast",
+    UserManageBlockedUsers => "user:manage:blocked_users",
+    UserManageChatColor => "user:manage:chat_color",
+    UserManageWhispers => "user:manage:whispers",
+    UserReadBlockedUsers => "user:read:blocked_users",
+    UserReadBroadcast => "user:read:broadcast",
+    UserReadChat => "user:read:chat",
+    UserReadEmail => "user:read:email",
+    UserReadEmotes => "user:read:emotes",
+    UserReadFollows => "user:read:follows",
+    UserReadModeratedChannels => "user:read:moderated_channels",
+    UserReadSubscriptions => "user:read:subscriptions",
+    UserReadWhispers => "user:read:whispers",
+    UserWriteChat => "user:write:chat",
+    WhispersEdit => "whispers:edit",
+    WhispersRead => "whispers:read",
+}
+
+impl From<String> for Scope {
+    fn from(name: String) -> Self {
+        Scope::from(name.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Scope::from(name))
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Scope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Scope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Scope::from)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScopeRequirement {
+    all_of: Vec<Scope>,
+    any_of: Vec<Vec<Scope>>,
+}
+
+impl ScopeRequirement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn require(&mut self, scope: Scope) {
+        if !self.all_of.contains(&scope) {
+            self.all_of.push(scope);
+        }
+    }
+
+    pub fn require_any(&mut self, scopes: impl IntoIterator<Item = Scope>) {
+        let scopes: Vec<Scope> = scopes.into_iter().collect();
+        match scopes.len() {
+            0 => {}
+            1 => self.require(scopes.into_iter().next().unwrap()),
+            _ => self.any_of.push(scopes)
+        }
+    }
+
+    pub fn all_of(&self) -> &[Scope] {
+        self.all_of.as_slice()
+    }
+
+    pub fn any_of(&self) -> &[Vec<Scope>] {
+        self.any_of.as_slice()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all_of.is_empty() && self.any_of.is_empty()
+    }
+
+    pub fn is_satisfied_by(&self, scopes: &[Scope]) -> bool {
+        self.all_of.iter().all(|scope| scopes.contains(scope))
+            && self.any_of.iter().all(|group| group.iter().any(|scope| scopes.contains(scope)))
+    }
+
+    // for unsatisfied "any of" groups, the first scope of the group is the one that gets requested
+    pub fn missing_from(&self, scopes: &[Scope]) -> Vec<Scope> {
+        let mut missing: Vec<Scope> = self.all_of.iter()
+            .filter(|scope| !scopes.contains(scope))
+            .cloned()
+            .collect();
+        for group in &self.any_of {
+            if !group.iter().any(|scope| scopes.contains(scope)) && !missing.contains(&group[0]) {
+                missing.push(group[0].clone());
+            }
+        }
+        missing
+    }
+}
+
+impl From<Scope> for ScopeRequirement {
+    fn from(scope: Scope) -> Self {
+        let mut requirement = Self::new();
+        requirement.require(scope);
+        requirement
+    }
+}
+
+impl Display for ScopeRequirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let all_of = self.all_of.iter().map(Scope::to_string);
+        let any_of = self.any_of.iter().map(|group| format!(
+            "one of ({})",
+            group.iter().map(Scope::as_str).collect::<Vec<_>>().join(", ")
+        ));
+        f.write_str(all_of.chain(any_of).collect::<Vec<_>>().join(", ").as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scope, ScopeRequirement};
+
+    #[test]
+    fn parses_known_and_unknown_scopes() {
+        assert_eq!("moderator:read:chatters".parse::<Scope>().unwrap(), Scope::ModeratorReadChatters);
+        assert_eq!(Scope::ModeratorReadChatters.to_string(), "moderator:read:chatters");
+        let unknown: Scope = "some:new:scope".parse().unwrap();
+        assert_eq!(unknown, Scope::Unknown("some:new:scope".to_string()));
+        assert_eq!(unknown.as_str(), "some:new:scope");
+    }
+
+    #[test]
+    fn checks_all_of_and_any_of_requirements() {
+        let mut requirement = ScopeRequirement::new();
+        requirement.require(Scope::ChannelManageBroadcast);
+        requirement.require_any(vec![Scope::ModeratorReadChatters, Scope::ModeratorManageChatMessages]);
+
+        assert!(requirement.is_satisfied_by(&[Scope::ChannelManageBroadcast, Scope::ModeratorManageChatMessages]));
+        assert!(!requirement.is_satisfied_by(&[Scope::ChannelManageBroadcast]));
+        assert!(!requirement.is_satisfied_by(&[Scope::ModeratorReadChatters]));
+        assert_eq!(
+            requirement.missing_from(&[Scope::ModeratorManageChatMessages]),
+            vec![Scope::ChannelManageBroadcast]
+        );
+        assert_eq!(
+            requirement.missing_from(&[]),
+            vec![Scope::ChannelManageBroadcast, Scope::ModeratorReadChatters]
+        );
+        assert_eq!(
+            requirement.to_string(),
+            "channel:manage:broadcast, one of (moderator:read:chatters, moderator:manage:chat_messages)"
+        );
+    }
+}
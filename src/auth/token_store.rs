@@ -0,0 +1,10 @@
+use futures::future::BoxFuture;
+
+use crate::auth::AccessToken;
+use crate::util::Result;
+
+pub trait TokenStore {
+    fn load_token<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<Option<AccessToken>>>;
+    fn save_token<'a>(&'a self, user_id: &'a str, token: &'a AccessToken) -> BoxFuture<'a, Result<()>>;
+    fn remove_token<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<()>>;
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use http::StatusCode;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::api::{ApiClient, HttpStatusError, TokenInfo};
+use crate::auth::{AccessToken, AuthEvent, AuthProvider, Scope};
+use crate::util::Result;
+
+// Twitch requires every user token to be validated at least once an hour
+pub const VALIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug)]
+pub enum TokenValidationEvent {
+    Validated {
+        scopes: Vec<Scope>,
+        expiry_date: Option<SystemTime>,
+    },
+    Invalidated,
+    // network hiccups etc. do not say anything about the token, so it is tried again next time
+    Failed {
+        message: String,
+    },
+}
+
+// validates the single token of a provider; the tokens of a MultiUserAuthProvider are not covered
+// and need a validator per user, e.g. over a StaticAuthProvider that is kept in sync
+pub struct TokenValidator {
+    task: JoinHandle<()>,
+    events: broadcast::Sender<TokenValidationEvent>,
+}
+
+impl TokenValidator {
+    // the first validation runs right away, so the receiver for it is handed out together with the validator
+    pub fn spawn(provider: Arc<dyn AuthProvider>) -> (Self, broadcast::Receiver<TokenValidationEvent>) {
+        Self::with_interval(provider, VALIDATION_INTERVAL)
+    }
+
+    pub fn with_interval(provider: Arc<dyn AuthProvider>, interval: Duration) -> (Self, broadcast::Receiver<TokenValidationEvent>) {
+        let (events, receiver) = broadcast::channel(16);
+        let task = tokio::spawn(Self::run(provider, interval, events.clone()));
+        (Self {
+            task,
+            events,
+        }, receiver)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TokenValidationEvent> {
+        self.events.subscribe()
+    }
+
+    async fn run(provider: Arc<dyn AuthProvider>, interval: Duration, events: broadcast::Sender<TokenValidationEvent>) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            // the first tick completes immediately, which validates the token on startup
+            interval.tick().await;
+            let result = Self::validate(provider.as_ref()).await;
+            if !Self::report(provider.as_ref(), &events, result) {
+                break;
+            }
+        }
+    }
+
+    async fn validate(provider: &dyn AuthProvider) -> Result<TokenValidationEvent> {
+        let token = provider.access_token().await?;
+        let result = ApiClient::get_token_info_for_access_token(provider.client_id(), token.access_token()).await;
+        Self::handle_token_info(provider, token, result)
+    }
+
+    // returns whether the token should be validated again
+    fn report(provider: &dyn AuthProvider, events: &broadcast::Sender<TokenValidationEvent>, result: Result<TokenValidationEvent>) -> bool {
+        let event = match result {
+            Ok(event) => event,
+            Err(e) => TokenValidationEvent::Failed {
+                message: e.to_string(),
+            }
+        };
+        let invalidated = matches!(event, TokenValidationEvent::Invalidated);
+        if invalidated {
+            if let Some(provider_events) = provider.events() {
+                provider_events.emit(AuthEvent::TokenInvalidated);
+            }
+        }
+        let _ = events.send(event);
+        !invalidated
+    }
+
+    fn handle_token_info(provider: &dyn AuthProvider, token: AccessToken, result: Result<TokenInfo>) -> Result<TokenValidationEvent> {
+        match result {
+            Ok(token_info) => {
+                provider.set_access_token(token.with_token_info(&token_info));
+                Ok(TokenValidationEvent::Validated {
+                    scopes: token_info.scopes().to_vec(),
+                    expiry_date: token_info.expiry_date(),
+                })
+            }
+            Err(e) => match e.downcast_ref::<HttpStatusError>() {
+                Some(error) if error.status() == StatusCode::UNAUTHORIZED => Ok(TokenValidationEvent::Invalidated),
+                _ => Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for TokenValidator {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use tokio::sync::broadcast;
+    use crate::api::HttpStatusError;
+    use crate::auth::{AccessToken, AuthEvent, AuthProvider, StaticAuthProvider};
+    use super::{TokenValidationEvent, TokenValidator};
+
+    #[test]
+    fn reports_rejected_tokens_as_invalidated() {
+        let provider = StaticAuthProvider::new("client-id".to_string(), "token");
+        let mut auth_events = provider.events().unwrap().subscribe();
+        let (events, mut receiver) = broadcast::channel(16);
+
+        let error = HttpStatusError::new("https://id.twitch.tv/oauth2/validate", StatusCode::UNAUTHORIZED, "invalid access token");
+        let result = TokenValidator::handle_token_info(&provider, AccessToken::with_access_token("token"), Err(Box::new(error)));
+        assert!(!TokenValidator::report(&provider, &events, result));
+        assert!(matches!(auth_events.try_recv(), Ok(AuthEvent::TokenInvalidated)));
+        assert!(matches!(receiver.try_recv(), Ok(TokenValidationEvent::Invalidated)));
+
+        let error = HttpStatusError::new("https://id.twitch.tv/oauth2/validate", StatusCode::SERVICE_UNAVAILABLE, "unavailable");
+        let result = TokenValidator::handle_token_info(&provider, AccessToken::with_access_token("token"), Err(Box::new(error)));
+        assert!(TokenValidator::report(&provider, &events, result));
+        assert!(auth_events.try_recv().is_err());
+        assert!(matches!(receiver.try_recv(), Ok(TokenValidationEvent::Failed { .. })));
+    }
+}
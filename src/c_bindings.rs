@@ -3,10 +3,10 @@
 use std::ffi::{CString, CStr};
 use ffi_support::FfiStr;
 use std::os::raw::c_char;
+use std::sync::Arc;
 
-use crate::User;
-use crate::api::ApiClient;
-use crate::auth::StaticAuthProvider;
+use crate::api::{ApiClient, User};
+use crate::auth::{Secret, StaticAuthProvider};
 use crate::auth::poly::{CAuthProvider, OwnedAuthProvider};
 
 #[derive(Debug)]
@@ -29,8 +29,8 @@ impl CUser {
 impl From<User> for CUser {
     fn from(user: User) -> CUser {
         CUser {
-            id: CString::new(user.id).expect("Could not create CString").into_raw(),
-            login: CString::new(user.login).expect("Could not create CString").into_raw(),
+            id: CString::new(user.id()).expect("Could not create CString").into_raw(),
+            login: CString::new(user.login()).expect("Could not create CString").into_raw(),
         }
     }
 }
@@ -41,19 +41,19 @@ lazy_static! {
 
 #[no_mangle]
 pub unsafe extern "C" fn createStaticAuthProvider(client_id: FfiStr, access_token: FfiStr) -> *mut CAuthProvider {
-    let provider_ptr = CAuthProvider::for_auth_provider(StaticAuthProvider::new(client_id.into_string(), access_token.into_string()));
+    let provider_ptr = CAuthProvider::for_auth_provider(StaticAuthProvider::new(client_id.into_string(), Secret::from(access_token.as_str())));
     provider_ptr
 }
 
 #[no_mangle]
 pub unsafe extern fn createApiClient(provider_ptr: *mut CAuthProvider) -> *mut ApiClient {
-    let client_ptr = Box::into_raw(Box::new(ApiClient::new(Box::new(OwnedAuthProvider::from_raw(provider_ptr)))));
+    let client_ptr = Box::into_raw(Box::new(ApiClient::new(Arc::new(OwnedAuthProvider::from_raw(provider_ptr)))));
     client_ptr
 }
 
 #[no_mangle]
 pub extern fn getMe(client_ptr: *mut ApiClient) -> *mut CUser {
-    let client = unsafe { client_ptr.as_mut().expect("Got NULL ptr") };
+    let client = unsafe { client_ptr.as_ref().expect("Got NULL ptr") };
     match RUNTIME.block_on(client.get_me()) {
         Ok(me) => Box::into_raw(Box::new(me.into())),
         Err(_) => {
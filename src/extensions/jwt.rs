@@ -0,0 +1,197 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::errors::ErrorKind;
+
+use crate::auth::Secret;
+use crate::util::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtensionRole {
+    Broadcaster,
+    Moderator,
+    Viewer,
+    External,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PubSubPerms {
+    #[serde(default)]
+    listen: Vec<String>,
+    #[serde(default)]
+    send: Vec<String>,
+}
+
+impl PubSubPerms {
+    pub fn new(listen: Vec<String>, send: Vec<String>) -> Self {
+        Self {
+            listen,
+            send,
+        }
+    }
+
+    pub fn listen(&self) -> &[String] {
+        self.listen.as_slice()
+    }
+
+    pub fn send(&self) -> &[String] {
+        self.send.as_slice()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionClaims {
+    exp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    channel_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opaque_user_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_id: Option<String>,
+    role: ExtensionRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_unlinked: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pubsub_perms: Option<PubSubPerms>,
+}
+
+impl ExtensionClaims {
+    // claims for tokens signed by the EBS itself; `user_id` has to be the extension owner's ID
+    pub fn external(user_id: impl Into<String>, expires_in: Duration) -> Self {
+        Self {
+            exp: (SystemTime::now() + expires_in).duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            channel_id: None,
+            opaque_user_id: None,
+            user_id: Some(user_id.into()),
+            role: ExtensionRole::External,
+            is_unlinked: None,
+            pubsub_perms: None,
+        }
+    }
+
+    pub fn with_channel_id(mut self, channel_id: impl Into<String>) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    pub fn with_pubsub_perms(mut self, pubsub_perms: PubSubPerms) -> Self {
+        self.pubsub_perms = Some(pubsub_perms);
+        self
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.exp
+    }
+
+    pub fn channel_id(&self) -> Option<&str> {
+        self.channel_id.as_deref()
+    }
+
+    pub fn opaque_user_id(&self) -> Option<&str> {
+        self.opaque_user_id.as_deref()
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref()
+    }
+
+    pub fn role(&self) -> ExtensionRole {
+        self.role
+    }
+
+    pub fn is_unlinked(&self) -> bool {
+        self.is_unlinked.unwrap_or(false)
+    }
+
+    pub fn pubsub_perms(&self) -> Option<&PubSubPerms> {
+        self.pubsub_perms.as_ref()
+    }
+}
+
+pub struct ExtensionJwt {
+    encoding_key: EncodingKey,
+    decoding_keys: Vec<DecodingKey>,
+    leeway: u64,
+}
+
+impl ExtensionJwt {
+    // the secret as shown in the extension console, i.e. base64 encoded
+    pub fn new(secret: &Secret) -> Result<Self> {
+        Ok(Self {
+            encoding_key: EncodingKey::from_base64_secret(secret.expose())?,
+            decoding_keys: vec![DecodingKey::from_base64_secret(secret.expose())?],
+            leeway: 60,
+        })
+    }
+
+    // keeps accepting tokens signed with a secret that is being rotated out; new tokens are always signed with the current one
+    pub fn with_previous_secret(mut self, secret: &Secret) -> Result<Self> {
+        self.decoding_keys.push(DecodingKey::from_base64_secret(secret.expose())?);
+        Ok(self)
+    }
+
+    pub fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    pub fn sign(&self, claims: &ExtensionClaims) -> Result<String> {
+        Ok(jsonwebtoken::encode(&Header::new(Algorithm::HS256), claims, &self.encoding_key)?)
+    }
+
+    pub fn verify(&self, token: &str) -> Result<ExtensionClaims> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = self.leeway;
+
+        let mut last_error = None;
+        for key in &self.decoding_keys {
+            match jsonwebtoken::decode::<ExtensionClaims>(token, key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) if *e.kind() == ErrorKind::InvalidSignature => last_error = Some(e),
+                Err(e) => return Err(Box::new(e))
+            }
+        }
+        Err(Box::new(last_error.unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use super::{ExtensionClaims, ExtensionJwt, ExtensionRole, PubSubPerms};
+    use crate::auth::Secret;
+
+    const SECRET: &str = "dHdpcmwgZXh0ZW5zaW9uIHNlY3JldA==";
+    const PREVIOUS_SECRET: &str = "b2xkIHR3aXJsIGV4dGVuc2lvbiBzZWNyZXQ=";
+
+    #[test]
+    fn signs_and_verifies_claims() {
+        let jwt = ExtensionJwt::new(&Secret::from(SECRET)).unwrap();
+        let claims = ExtensionClaims::external("1234", Duration::from_secs(60))
+            .with_channel_id("5678")
+            .with_pubsub_perms(PubSubPerms::new(vec![], vec!["broadcast".to_string()]));
+        let verified = jwt.verify(jwt.sign(&claims).unwrap().as_str()).unwrap();
+        assert_eq!(verified, claims);
+        assert_eq!(verified.role(), ExtensionRole::External);
+        assert_eq!(verified.pubsub_perms().unwrap().send(), ["broadcast"]);
+    }
+
+    #[test]
+    fn accepts_tokens_signed_with_previous_secret() {
+        let old_token = ExtensionJwt::new(&Secret::from(PREVIOUS_SECRET)).unwrap()
+            .sign(&ExtensionClaims::external("1234", Duration::from_secs(60))).unwrap();
+        assert!(ExtensionJwt::new(&Secret::from(SECRET)).unwrap().verify(old_token.as_str()).is_err());
+        let jwt = ExtensionJwt::new(&Secret::from(SECRET)).unwrap().with_previous_secret(&Secret::from(PREVIOUS_SECRET)).unwrap();
+        assert!(jwt.verify(old_token.as_str()).is_ok());
+    }
+
+    #[test]
+    fn tolerates_clock_skew_within_leeway() {
+        let jwt = ExtensionJwt::new(&Secret::from(SECRET)).unwrap().with_leeway(30);
+        let mut claims = ExtensionClaims::external("1234", Duration::from_secs(0));
+        claims.exp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 10;
+        assert!(jwt.verify(jwt.sign(&claims).unwrap().as_str()).is_ok());
+        claims.exp -= 60;
+        assert!(jwt.verify(jwt.sign(&claims).unwrap().as_str()).is_err());
+    }
+}
@@ -1,4 +1,3 @@
-#![feature(in_band_lifetimes)]
 #![allow(dead_code)]
 #![warn(unused_imports)]
 
@@ -12,27 +11,18 @@ extern crate lazy_static;
 extern crate chrono;
 extern crate connect;
 
-mod api;
-mod auth;
+pub mod api;
+pub mod auth;
+pub mod extensions;
 mod util;
 
-#[derive(Deserialize, Debug)]
-struct UserResponse {
-    data: Vec<User>,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct User {
-    id: String,
-    login: String,
-}
-
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
     use crate::util::Result;
     use crate::auth::StaticAuthProvider;
     use crate::api::ApiClient;
-    use ffi_support::{FfiStr, rust_string_to_c};
+    use ffi_support::{FfiStr, destroy_c_string, rust_string_to_c};
 
     #[tokio::test]
     async fn a_test() -> Result<()> {
@@ -45,8 +35,8 @@ mod tests {
             Ok(val) => val,
             Err(_e) => panic!("TWITCH_ACCESS_TOKEN was not set")
         };
-        let mut auth = StaticAuthProvider::new(client_id, access_token);
-        let mut client = ApiClient::new(Box::new(auth));
+        let auth = StaticAuthProvider::new(client_id, access_token);
+        let client = ApiClient::new(Arc::new(auth));
         let user = client.get_me().await?;
         println!("user: {:#?}", user);
         Ok(())
@@ -65,8 +55,10 @@ mod tests {
             };
 
             let client_id_ffi = FfiStr::from_raw(rust_string_to_c(client_id));
-            let access_token_ffi = FfiStr::from_raw(rust_string_to_c(access_token));
-            let mut auth = crate::c_bindings::createStaticAuthProvider(client_id_ffi, access_token_ffi);
+            let access_token_ptr = rust_string_to_c(access_token);
+            let mut auth = crate::c_bindings::createStaticAuthProvider(client_id_ffi, FfiStr::from_raw(access_token_ptr));
+            // the provider keeps its own copy of the token, so ours can go right away
+            destroy_c_string(access_token_ptr);
             let mut client = crate::c_bindings::createApiClient(auth);
 
             let user = crate::c_bindings::getMe(client);